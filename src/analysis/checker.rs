@@ -1,4 +1,7 @@
+use super::functional_classes::ClassFactory;
+use super::sqlalchemy::is_declarative_base_factory;
 use crate::ast;
+use crate::render::renderer::{Visibility, VisibilityPolicy};
 use ast::{helpers::collect_import_from_member, identifier::Identifier as _, name::QualifiedName};
 use itertools::Itertools as _;
 use ruff_linter::Locator;
@@ -9,12 +12,29 @@ use ruff_python_semantic::{
 };
 use ruff_python_stdlib::builtins::{python_builtins, MAGIC_GLOBALS};
 use ruff_text_size::TextRange;
+use std::collections::HashSet;
 
 /// Slimmed down version of the `Checker` struct from the `ruff_python_semantic` crate.
 pub struct Checker<'a> {
     stylist: &'a Stylist<'a>,
     locator: &'a Locator<'a>,
     semantic: SemanticModel<'a>,
+    /// Dotted module path of the file being analyzed (e.g. `["pkg", "models"]`), if known
+    module_path: &'a [String],
+    is_package: bool,
+    /// Whether the file being analyzed is a `.pyi` stub
+    is_stub: bool,
+    /// Names of the classes defined at module level (including under `if`/`try`/`with`)
+    module_classes: HashSet<&'a str>,
+    /// Names of the module-level functions and classes decorated with `dataclass_transform`
+    dataclass_transforms: HashSet<&'a str>,
+    /// Names assigned a SQLAlchemy declarative base at module level, e.g.
+    /// `Base = declarative_base()`
+    declarative_bases: HashSet<&'a str>,
+    /// How member names map to visibility
+    visibility_policy: VisibilityPolicy,
+    /// Whether docstrings supply the types that annotations leave out
+    docstring_types: bool,
 }
 
 impl<'a> Checker<'a> {
@@ -27,11 +47,210 @@ impl<'a> Checker<'a> {
             stylist,
             locator,
             semantic,
+            module_path: &[],
+            is_package: false,
+            is_stub: false,
+            module_classes: HashSet::new(),
+            dataclass_transforms: HashSet::new(),
+            declarative_bases: HashSet::new(),
+            visibility_policy: VisibilityPolicy::default(),
+            docstring_types: false,
         };
         checker.bind_builtins();
         checker
     }
 
+    /// Set the module path of the file being analyzed, used to resolve relative imports and
+    /// to qualify class identities. Must be called before `see_imports`.
+    pub const fn set_module_path(&mut self, module_path: &'a [String], is_package: bool) {
+        self.module_path = module_path;
+        self.is_package = is_package;
+    }
+
+    pub const fn set_stub(&mut self, is_stub: bool) {
+        self.is_stub = is_stub;
+    }
+
+    #[must_use]
+    pub const fn is_stub(&self) -> bool {
+        self.is_stub
+    }
+
+    pub const fn set_docstring_types(&mut self, docstring_types: bool) {
+        self.docstring_types = docstring_types;
+    }
+
+    #[must_use]
+    pub const fn docstring_types(&self) -> bool {
+        self.docstring_types
+    }
+
+    pub const fn set_visibility_policy(&mut self, visibility_policy: VisibilityPolicy) {
+        self.visibility_policy = visibility_policy;
+    }
+
+    /// The visibility of a member name under the visibility policy.
+    #[must_use]
+    pub fn visibility_for_name(&self, name: &str) -> Visibility {
+        self.visibility_policy.visibility_for_name(name)
+    }
+
+    /// Qualify a class's `__qualname__` with the current module path, if known.
+    #[must_use]
+    pub fn qualify(&self, qualname: &str) -> String {
+        if self.module_path.is_empty() {
+            qualname.to_owned()
+        } else {
+            format!("{}.{qualname}", self.module_path.join("."))
+        }
+    }
+
+    /// Returns the project-qualified identity of a referenced class, given its name as written
+    /// (for classes defined in this module) or as resolved through the imports.
+    ///
+    /// Returns `None` when the name is neither defined here nor imported.
+    #[must_use]
+    pub fn class_id_for_name(&self, name: &str) -> Option<String> {
+        if self.module_classes.contains(name) {
+            Some(self.qualify(name))
+        } else if name.contains('.') {
+            Some(name.to_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Names of the module-level functions and classes decorated with `dataclass_transform`,
+    /// whose decorated or derived classes are dataclass-like.
+    #[must_use]
+    pub const fn dataclass_transforms(&self) -> &HashSet<&'a str> {
+        &self.dataclass_transforms
+    }
+
+    /// Names assigned the result of `declarative_base()`, whose subclasses are SQLAlchemy
+    /// models.
+    #[must_use]
+    pub const fn declarative_bases(&self) -> &HashSet<&'a str> {
+        &self.declarative_bases
+    }
+
+    /// Whether the decorators include `typing.dataclass_transform` (called or not).
+    fn is_dataclass_transform(&self, decorators: &[ast::Decorator]) -> bool {
+        decorators.iter().any(|decorator| {
+            let expression = match &decorator.expression {
+                ast::Expr::Call(call) => &call.func,
+                expression => expression,
+            };
+            self.semantic
+                .resolve_qualified_name(expression)
+                .is_some_and(|name| {
+                    matches!(
+                        name.segments(),
+                        ["typing" | "typing_extensions", "dataclass_transform"]
+                    )
+                })
+        })
+    }
+
+    /// Record the names of the classes defined at module level, descending into compound
+    /// statements the same way class discovery does.
+    pub fn see_classes(&mut self, stmts: &'a [ast::Stmt]) {
+        for stmt in stmts {
+            match stmt {
+                ast::Stmt::ClassDef(class) => {
+                    self.module_classes.insert(class.name.as_str());
+                    if self.is_dataclass_transform(&class.decorator_list) {
+                        self.dataclass_transforms.insert(class.name.as_str());
+                    }
+                }
+                ast::Stmt::FunctionDef(function) => {
+                    if self.is_dataclass_transform(&function.decorator_list) {
+                        self.dataclass_transforms.insert(function.name.as_str());
+                    }
+                }
+                // Functional declarations, e.g. `Point = namedtuple("Point", "x y")`
+                ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                    if let ([ast::Expr::Name(target)], ast::Expr::Call(call)) =
+                        (targets.as_slice(), value.as_ref())
+                    {
+                        if ClassFactory::from_call(call, &self.semantic).is_some() {
+                            self.module_classes.insert(target.id.as_str());
+                        } else if self
+                            .semantic
+                            .resolve_qualified_name(&call.func)
+                            .is_some_and(|name| is_declarative_base_factory(name.segments()))
+                        {
+                            self.declarative_bases.insert(target.id.as_str());
+                        }
+                    }
+                }
+                ast::Stmt::If(ast::StmtIf {
+                    body,
+                    elif_else_clauses,
+                    ..
+                }) => {
+                    self.see_classes(body);
+                    for clause in elif_else_clauses {
+                        self.see_classes(&clause.body);
+                    }
+                }
+                ast::Stmt::Try(ast::StmtTry {
+                    body,
+                    handlers,
+                    orelse,
+                    finalbody,
+                    ..
+                }) => {
+                    self.see_classes(body);
+                    for handler in handlers {
+                        let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                        self.see_classes(&handler.body);
+                    }
+                    self.see_classes(orelse);
+                    self.see_classes(finalbody);
+                }
+                ast::Stmt::With(ast::StmtWith { body, .. }) => self.see_classes(body),
+                ast::Stmt::Match(ast::StmtMatch { cases, .. }) => {
+                    for case in cases {
+                        self.see_classes(&case.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Resolve a relative import (e.g. `from ..models import User`) against the module path.
+    /// Returns `None` for absolute imports, if the module path is unknown, or if the import
+    /// escapes the package root.
+    fn resolve_relative_import(
+        &self,
+        level: u32,
+        module: Option<&'a str>,
+        member: &'a str,
+    ) -> Option<QualifiedName<'a>> {
+        if self.module_path.is_empty() {
+            return None;
+        }
+
+        let package_len = if self.is_package {
+            self.module_path.len()
+        } else {
+            self.module_path.len() - 1
+        };
+        let parents = usize::try_from(level).ok()?.checked_sub(1)?;
+        let base_len = package_len.checked_sub(parents)?;
+
+        Some(
+            self.module_path[..base_len]
+                .iter()
+                .map(String::as_str)
+                .chain(module.into_iter().flat_map(|module| module.split('.')))
+                .chain(std::iter::once(member))
+                .collect(),
+        )
+    }
+
     fn bind_builtins(&mut self) {
         for builtin in python_builtins(u8::MAX, false).chain(MAGIC_GLOBALS.iter().copied()) {
             // Add the builtin to the scope.
@@ -239,8 +458,11 @@ impl<'a> Checker<'a> {
                             // Attempt to resolve any relative imports; but if we don't know the current
                             // module path, or the relative import extends beyond the package root,
                             // fallback to a literal representation (e.g., `[".", "foo"]`).
-                            let qualified_name =
-                                collect_import_from_member(level, module, &alias.name);
+                            let qualified_name = self
+                                .resolve_relative_import(level, module, &alias.name)
+                                .unwrap_or_else(|| {
+                                    collect_import_from_member(level, module, &alias.name)
+                                });
                             self.add_binding(
                                 name,
                                 alias.identifier(),
@@ -252,6 +474,33 @@ impl<'a> Checker<'a> {
                         }
                     }
                 }
+                // Imports guarded by `if TYPE_CHECKING:`, `try: ... except ImportError:`, etc.
+                ast::Stmt::If(ast::StmtIf {
+                    body,
+                    elif_else_clauses,
+                    ..
+                }) => {
+                    self.see_imports(body);
+                    for clause in elif_else_clauses {
+                        self.see_imports(&clause.body);
+                    }
+                }
+                ast::Stmt::Try(ast::StmtTry {
+                    body,
+                    handlers,
+                    orelse,
+                    finalbody,
+                    ..
+                }) => {
+                    self.see_imports(body);
+                    for handler in handlers {
+                        let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                        self.see_imports(&handler.body);
+                    }
+                    self.see_imports(orelse);
+                    self.see_imports(finalbody);
+                }
+                ast::Stmt::With(ast::StmtWith { body, .. }) => self.see_imports(body),
                 _ => {}
             }
         }
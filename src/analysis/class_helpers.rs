@@ -1,5 +1,10 @@
 /// Helper traits and utilities for working with Python class definitions
+use super::django::is_django_model_base;
+use super::enums::enum_class_type;
+use super::functional_classes::ClassFactory;
+use super::sqlalchemy::is_declarative_base;
 use crate::ast;
+use crate::render::renderer::ClassType;
 use ruff_python_ast::name::QualifiedName;
 use ruff_python_ast::Arguments;
 use ruff_python_semantic::analyze::class::is_enumeration;
@@ -37,8 +42,24 @@ pub trait ClassDefHelpers {
     fn is_abstract(&self, semantic: &SemanticModel) -> bool;
     fn is_final(&self, semantic: &SemanticModel) -> bool;
     fn is_enum(&self, semantic: &SemanticModel) -> bool;
+    fn enum_type(&self, semantic: &SemanticModel) -> ClassType;
     fn is_protocol(&self, semantic: &SemanticModel) -> bool;
     fn is_dataclass(&self, semantic: &SemanticModel) -> bool;
+    fn is_named_tuple(&self, semantic: &SemanticModel) -> bool;
+    fn is_typed_dict(&self, semantic: &SemanticModel) -> bool;
+    fn is_pydantic_model(&self, semantic: &SemanticModel) -> bool;
+    fn is_attrs_class(&self, semantic: &SemanticModel) -> bool;
+    fn is_msgspec_struct(&self, semantic: &SemanticModel) -> bool;
+    fn is_django_model(&self, semantic: &SemanticModel) -> bool;
+    fn is_sqlalchemy_model(&self, semantic: &SemanticModel) -> bool;
+}
+
+/// Returns the function a decorator refers to, looking through calls like `@define(frozen=True)`.
+pub fn decorator_function(decorator: &ast::Decorator) -> &ast::Expr {
+    match &decorator.expression {
+        ast::Expr::Call(call) => &call.func,
+        expression => expression,
+    }
 }
 
 impl ClassDefHelpers for ast::StmtClassDef {
@@ -65,6 +86,16 @@ impl ClassDefHelpers for ast::StmtClassDef {
         is_enumeration(self, semantic)
     }
 
+    /// The kind of enum (e.g. `IntEnum` or `Flag`) given by the class's `enum` base; mixins
+    /// such as `class Color(str, Enum)` and enums with other bases are plain enumerations.
+    fn enum_type(&self, semantic: &SemanticModel) -> ClassType {
+        self.bases()
+            .iter()
+            .filter_map(|base| semantic.resolve_qualified_name(base))
+            .find_map(|name| enum_class_type(name.segments()))
+            .unwrap_or(ClassType::Enumeration)
+    }
+
     fn is_protocol(&self, semantic: &SemanticModel) -> bool {
         let Some(Arguments { args, keywords, .. }) = self.arguments.as_deref() else {
             return false;
@@ -89,8 +120,10 @@ impl ClassDefHelpers for ast::StmtClassDef {
 
     fn is_dataclass(&self, semantic: &SemanticModel) -> bool {
         for decorator in &self.decorator_list {
-            // Check the decorator expression directly (for @dataclass)
-            if let Some(qualified_name) = semantic.resolve_qualified_name(&decorator.expression) {
+            // Check the decorator function (for @dataclass and @dataclass(frozen=True))
+            if let Some(qualified_name) =
+                semantic.resolve_qualified_name(decorator_function(decorator))
+            {
                 if matches!(
                     qualified_name.segments(),
                     ["dataclasses", "dataclass"] | ["pydantic", "dataclasses", "dataclass"]
@@ -101,6 +134,86 @@ impl ClassDefHelpers for ast::StmtClassDef {
         }
         false
     }
+
+    fn is_named_tuple(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| match base {
+            // class Point(namedtuple("Point", "x y")): ...
+            ast::Expr::Call(call) => matches!(
+                ClassFactory::from_call(call, semantic),
+                Some(ClassFactory::NamedTuple | ClassFactory::TypedNamedTuple)
+            ),
+            _ => semantic.resolve_qualified_name(base).is_some_and(|name| {
+                matches!(
+                    name.segments(),
+                    ["typing" | "typing_extensions", "NamedTuple"]
+                )
+            }),
+        })
+    }
+
+    fn is_pydantic_model(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| {
+            semantic.resolve_qualified_name(base).is_some_and(|name| {
+                matches!(
+                    name.segments(),
+                    [
+                        "pydantic" | "pydantic_settings",
+                        "BaseModel" | "BaseSettings"
+                    ] | ["pydantic", "main" | "v1", "BaseModel"]
+                        | ["sqlmodel", "SQLModel"]
+                )
+            })
+        })
+    }
+
+    fn is_attrs_class(&self, semantic: &SemanticModel) -> bool {
+        self.decorator_list.iter().any(|decorator| {
+            semantic
+                .resolve_qualified_name(decorator_function(decorator))
+                .is_some_and(|name| {
+                    matches!(
+                        name.segments(),
+                        ["attr", "s" | "attrs" | "dataclass"]
+                            | ["attr" | "attrs", "define" | "frozen" | "mutable"]
+                    )
+                })
+        })
+    }
+
+    fn is_msgspec_struct(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| {
+            semantic
+                .resolve_qualified_name(base)
+                .is_some_and(|name| matches!(name.segments(), ["msgspec", "Struct"]))
+        })
+    }
+
+    fn is_django_model(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| {
+            semantic
+                .resolve_qualified_name(base)
+                .is_some_and(|name| is_django_model_base(name.segments()))
+        })
+    }
+
+    fn is_sqlalchemy_model(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| {
+            semantic
+                .resolve_qualified_name(base)
+                .is_some_and(|name| is_declarative_base(name.segments()))
+        })
+    }
+
+    fn is_typed_dict(&self, semantic: &SemanticModel) -> bool {
+        self.bases().iter().any(|base| {
+            semantic.resolve_qualified_name(base).is_some_and(|name| {
+                matches!(
+                    name.segments(),
+                    ["typing" | "typing_extensions", "TypedDict"]
+                )
+            })
+        })
+    }
 }
 
 #[cfg(test)]
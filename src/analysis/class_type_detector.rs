@@ -1,21 +1,31 @@
 use super::checker::Checker;
-use super::class_helpers::{is_abc_qualified_name, ClassDefHelpers};
+use super::class_helpers::{decorator_function, is_abc_qualified_name, ClassDefHelpers};
 /// Utilities for detecting and classifying Python class types
 use crate::ast;
 use crate::render::renderer::ClassType;
 use ruff_python_semantic::SemanticModel;
+use std::collections::HashSet;
 
 /// Determines the type of a Python class based on its properties and decorators.
 ///
 /// The precedence order is:
 /// 1. Interface (Protocol)
-/// 2. Dataclass
-/// 3. Abstract
-/// 4. Enumeration
-/// 5. Final
-/// 6. Regular
+/// 2. `NamedTuple`
+/// 3. `TypedDict`
+/// 4. Dataclass (including `dataclass_transform` classes)
+/// 5. Pydantic model
+/// 6. attrs class
+/// 7. msgspec struct
+/// 8. Django model
+/// 9. SQLAlchemy model
+/// 10. Abstract
+/// 11. Enumeration (including `IntEnum`, `StrEnum` and `Flag`)
+/// 12. Final
+/// 13. Regular
 pub struct ClassTypeDetector<'a> {
     semantic: &'a SemanticModel<'a>,
+    dataclass_transforms: &'a HashSet<&'a str>,
+    declarative_bases: &'a HashSet<&'a str>,
 }
 
 impl<'a> ClassTypeDetector<'a> {
@@ -23,6 +33,8 @@ impl<'a> ClassTypeDetector<'a> {
     pub const fn new(checker: &'a Checker) -> Self {
         Self {
             semantic: checker.semantic(),
+            dataclass_transforms: checker.dataclass_transforms(),
+            declarative_bases: checker.declarative_bases(),
         }
     }
 
@@ -30,12 +42,26 @@ impl<'a> ClassTypeDetector<'a> {
     pub fn detect_type(&self, class: &ast::StmtClassDef) -> ClassType {
         if class.is_protocol(self.semantic) {
             ClassType::Interface
-        } else if class.is_dataclass(self.semantic) {
+        } else if class.is_named_tuple(self.semantic) {
+            ClassType::NamedTuple
+        } else if class.is_typed_dict(self.semantic) {
+            ClassType::TypedDict
+        } else if class.is_dataclass(self.semantic) || self.is_dataclass_transformed(class) {
             ClassType::Dataclass
+        } else if class.is_pydantic_model(self.semantic) {
+            ClassType::PydanticModel
+        } else if class.is_attrs_class(self.semantic) {
+            ClassType::AttrsClass
+        } else if class.is_msgspec_struct(self.semantic) {
+            ClassType::MsgspecStruct
+        } else if class.is_django_model(self.semantic) {
+            ClassType::DjangoModel
+        } else if class.is_sqlalchemy_model(self.semantic) || self.derives_declarative_base(class) {
+            ClassType::SqlAlchemyModel
         } else if self.is_abstract(class) {
             ClassType::Abstract
         } else if class.is_enum(self.semantic) {
-            ClassType::Enumeration
+            class.enum_type(self.semantic)
         } else if class.is_final(self.semantic) {
             ClassType::Final
         } else {
@@ -43,6 +69,33 @@ impl<'a> ClassTypeDetector<'a> {
         }
     }
 
+    /// Check if a class is decorated with, derives from, or uses as its metaclass something
+    /// decorated with `typing.dataclass_transform` in the same module.
+    fn is_dataclass_transformed(&self, class: &ast::StmtClassDef) -> bool {
+        let is_transform = |expr: &ast::Expr| {
+            expr.as_name_expr()
+                .is_some_and(|name| self.dataclass_transforms.contains(name.id.as_str()))
+        };
+
+        class
+            .decorator_list
+            .iter()
+            .any(|decorator| is_transform(decorator_function(decorator)))
+            || class.bases().iter().any(is_transform)
+            || class
+                .keywords()
+                .iter()
+                .any(|keyword| is_transform(&keyword.value))
+    }
+
+    /// Check if a class derives from a base created by `declarative_base()` in the same module.
+    fn derives_declarative_base(&self, class: &ast::StmtClassDef) -> bool {
+        class.bases().iter().any(|base| {
+            base.as_name_expr()
+                .is_some_and(|name| self.declarative_bases.contains(name.id.as_str()))
+        })
+    }
+
     /// Check if a class is abstract.
     /// This includes classes that:
     /// - Have abstract methods (via decorators)
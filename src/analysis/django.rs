@@ -0,0 +1,192 @@
+/// Extraction of Django model fields and the relationships declared by `ForeignKey`,
+/// `OneToOneField` and `ManyToManyField`
+use super::checker::Checker;
+use crate::ast;
+use crate::render::renderer::Cardinality;
+use ruff_python_ast::Expr;
+
+/// Returns true if the qualified name is Django's model base class.
+pub fn is_django_model_base(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        ["django", "db", "models", "Model"] | ["django", "db", "models", "base", "Model"]
+    )
+}
+
+/// The model a relationship field points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelatedModel {
+    /// `"self"`, the model declaring the field
+    SelfRef,
+    /// A model given by name, as an expression (`Author`) or a string (`"Author"`,
+    /// `"books.Author"`); the app label of a string reference is dropped
+    Named(String),
+}
+
+/// A relationship declared by a Django field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DjangoRelation {
+    pub model: RelatedModel,
+    pub cardinality: Cardinality,
+}
+
+/// A Django model field, e.g. `title = models.CharField(max_length=100)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DjangoField {
+    kind: DjangoFieldKind,
+    /// `null=True`
+    nullable: bool,
+    pub relation: Option<DjangoRelation>,
+}
+
+/// What a field holds: a plain value, or one or many instances of the related model
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DjangoFieldKind {
+    Value(String),
+    ToOne,
+    ToMany,
+}
+
+impl DjangoField {
+    /// Parse a call to a field class from `django.db.models`, or return `None` if the call
+    /// is something else.
+    pub fn from_call(call: &ast::ExprCall, checker: &Checker) -> Option<Self> {
+        let qualified_name = checker.semantic().resolve_qualified_name(&call.func)?;
+        let field_class = match qualified_name.segments() {
+            ["django", "db", "models", field_class]
+            | ["django", "db", "models", "fields", .., field_class] => *field_class,
+            _ => return None,
+        };
+
+        let arguments = &call.arguments;
+        let nullable = arguments.find_keyword("null").is_some_and(|keyword| {
+            matches!(
+                keyword.value,
+                Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: true, .. })
+            )
+        });
+
+        let (kind, cardinality) = match field_class {
+            "ForeignKey" | "OneToOneField" => (
+                DjangoFieldKind::ToOne,
+                if nullable {
+                    Cardinality::ZeroOrOne
+                } else {
+                    Cardinality::One
+                },
+            ),
+            "ManyToManyField" => (DjangoFieldKind::ToMany, Cardinality::Many),
+            _ => {
+                return Some(Self {
+                    kind: DjangoFieldKind::Value(value_type(field_class).to_owned()),
+                    nullable,
+                    relation: None,
+                });
+            }
+        };
+
+        let relation = arguments
+            .find_argument_value("to", 0)
+            .and_then(|to| related_model(to, checker))
+            .map(|model| DjangoRelation { model, cardinality });
+
+        Some(Self {
+            kind,
+            nullable,
+            relation,
+        })
+    }
+
+    /// The Python type of the field's value, given the name of the model declaring it
+    /// (for `"self"` references).
+    #[must_use]
+    pub fn type_annotation(&self, model_name: &str) -> String {
+        let related = match self.relation.as_ref().map(|relation| &relation.model) {
+            Some(RelatedModel::SelfRef) => model_name,
+            Some(RelatedModel::Named(name)) => name.rsplit('.').next().unwrap_or(name),
+            None => "Any",
+        };
+
+        let type_annotation = match &self.kind {
+            DjangoFieldKind::Value(value_type) => value_type.clone(),
+            DjangoFieldKind::ToOne => related.to_owned(),
+            DjangoFieldKind::ToMany => format!("list[{related}]"),
+        };
+
+        // A nullable many-to-many field is still a (possibly empty) collection
+        if self.nullable && self.kind != DjangoFieldKind::ToMany {
+            format!("{type_annotation} | None")
+        } else {
+            type_annotation
+        }
+    }
+}
+
+/// Resolve the model argument of a relationship field.
+fn related_model(to: &Expr, checker: &Checker) -> Option<RelatedModel> {
+    match to {
+        Expr::StringLiteral(ast::ExprStringLiteral { value, .. }) => {
+            let name = value.to_str();
+            if name == "self" {
+                Some(RelatedModel::SelfRef)
+            } else {
+                // `"app_label.Model"` names the model class within a Django app
+                let name = name.rsplit('.').next().unwrap_or(name);
+                Some(RelatedModel::Named(name.to_owned()))
+            }
+        }
+        // A model defined in this module is named as written, and an imported one by the
+        // qualified name its import resolves to
+        Expr::Name(ast::ExprName { id, .. }) => {
+            let name = if checker.class_id_for_name(id).is_some() {
+                id.to_string()
+            } else {
+                checker
+                    .semantic()
+                    .resolve_qualified_name(to)
+                    .map_or_else(|| id.to_string(), |name| name.segments().join("."))
+            };
+            Some(RelatedModel::Named(name))
+        }
+        Expr::Attribute(_) => checker
+            .semantic()
+            .resolve_qualified_name(to)
+            .map(|name| RelatedModel::Named(name.segments().join("."))),
+        _ => None,
+    }
+}
+
+/// The Python type stored by a Django field class; unknown fields keep their class name.
+fn value_type(field_class: &str) -> &str {
+    match field_class {
+        "CharField"
+        | "TextField"
+        | "EmailField"
+        | "SlugField"
+        | "URLField"
+        | "GenericIPAddressField"
+        | "FilePathField" => "str",
+        "IntegerField"
+        | "SmallIntegerField"
+        | "BigIntegerField"
+        | "PositiveIntegerField"
+        | "PositiveSmallIntegerField"
+        | "PositiveBigIntegerField"
+        | "AutoField"
+        | "BigAutoField"
+        | "SmallAutoField" => "int",
+        "FloatField" => "float",
+        "DecimalField" => "Decimal",
+        "BooleanField" | "NullBooleanField" => "bool",
+        "DateField" => "date",
+        "DateTimeField" => "datetime",
+        "TimeField" => "time",
+        "DurationField" => "timedelta",
+        "UUIDField" => "UUID",
+        "BinaryField" => "bytes",
+        "JSONField" => "Any",
+        "FileField" => "FieldFile",
+        "ImageField" => "ImageFieldFile",
+        _ => field_class,
+    }
+}
@@ -0,0 +1,316 @@
+/// Parameter and return types documented in docstrings, for code that describes its types
+/// there rather than in annotations. Google (`Args:`/`Returns:`), NumPy (`Parameters` and
+/// `Returns` tables) and Sphinx (`:type x:`/`:rtype:`) styles are understood.
+use crate::ast;
+use ruff_python_ast::{Expr, Stmt};
+use std::collections::HashMap;
+
+/// Returns the docstring of a function or class body, if it starts with a string literal.
+pub fn docstring(body: &[Stmt]) -> Option<&str> {
+    let Some(Stmt::Expr(ast::StmtExpr { value, .. })) = body.first() else {
+        return None;
+    };
+    match value.as_ref() {
+        Expr::StringLiteral(ast::ExprStringLiteral { value, .. }) => Some(value.to_str()),
+        _ => None,
+    }
+}
+
+/// A section of a Google or NumPy style docstring that documents types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Parameters,
+    Returns,
+}
+
+impl Section {
+    /// The section introduced by a Google style header, e.g. `Args:`.
+    fn from_google_header(line: &str) -> Option<Self> {
+        match line.strip_suffix(':')? {
+            "Args" | "Arguments" | "Parameters" | "Params" | "Keyword Args"
+            | "Keyword Arguments" | "Other Parameters" => Some(Self::Parameters),
+            "Returns" | "Return" => Some(Self::Returns),
+            _ => None,
+        }
+    }
+
+    /// The section introduced by a NumPy style header, which is underlined with dashes.
+    fn from_numpy_header(line: &str) -> Option<Self> {
+        match line {
+            "Parameters" | "Other Parameters" => Some(Self::Parameters),
+            "Returns" => Some(Self::Returns),
+            _ => None,
+        }
+    }
+}
+
+/// Docstring conventions differ in how an entry writes its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// `name (type): description` and `type: description`
+    Google,
+    /// `name : type`, with the description indented below
+    NumPy,
+}
+
+/// The types a docstring gives to a function's parameters and return value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocstringTypes {
+    parameters: HashMap<String, String>,
+    returns: Vec<String>,
+}
+
+impl DocstringTypes {
+    #[must_use]
+    pub fn parse(docstring: &str) -> Self {
+        let mut types = Self::default();
+        let lines: Vec<&str> = docstring.lines().collect();
+
+        // The open section, its style, and the indentation of its entries once known
+        let mut section: Option<(Section, Style, Option<usize>)> = None;
+        let mut header_indent = 0;
+        let mut idx = 0;
+        while idx < lines.len() {
+            let line = lines[idx];
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            idx += 1;
+
+            if trimmed.is_empty() {
+                continue;
+            }
+            if trimmed.starts_with(':') {
+                types.parse_sphinx_field(trimmed);
+                continue;
+            }
+            let underlined = lines.get(idx).is_some_and(|next| {
+                let next = next.trim();
+                next.len() >= 3 && next.chars().all(|c| c == '-')
+            });
+            if underlined {
+                // Any NumPy header ends the open section
+                section =
+                    Section::from_numpy_header(trimmed).map(|kind| (kind, Style::NumPy, None));
+                header_indent = indent;
+                idx += 1;
+                continue;
+            }
+            if let Some(kind) = Section::from_google_header(trimmed) {
+                section = Some((kind, Style::Google, None));
+                header_indent = indent;
+                continue;
+            }
+
+            let Some((kind, style, entry_indent)) = section.as_mut() else {
+                continue;
+            };
+            if *style == Style::Google && indent <= header_indent {
+                // Dedented text (e.g. `Raises:`) ends a Google section
+                section = None;
+                continue;
+            }
+            match *entry_indent {
+                // Deeper lines describe the entry above
+                Some(entry_indent) if indent > entry_indent => continue,
+                Some(_) => {}
+                None => *entry_indent = Some(indent),
+            }
+            match (*kind, *style) {
+                (Section::Parameters, Style::Google) => types.parse_google_parameter(trimmed),
+                (Section::Parameters, Style::NumPy) => types.parse_numpy_parameter(trimmed),
+                (Section::Returns, Style::Google) => {
+                    // Only the first line names the type; the rest continues the description
+                    if types.returns.is_empty() {
+                        if let Some((type_name, _)) = trimmed.split_once(':') {
+                            types.returns.extend(normalize_type(type_name));
+                        }
+                    }
+                }
+                (Section::Returns, Style::NumPy) => {
+                    let type_name = trimmed
+                        .split_once(" : ")
+                        .map_or(trimmed, |(_, type_name)| type_name);
+                    types.returns.extend(normalize_type(type_name));
+                }
+            }
+        }
+        types
+    }
+
+    /// `name (type): description`; entries without a type are skipped.
+    fn parse_google_parameter(&mut self, entry: &str) {
+        let Some((head, _)) = entry.split_once(':') else {
+            return;
+        };
+        let Some((name, type_name)) = head.split_once('(') else {
+            return;
+        };
+        let Some(type_name) = type_name.trim().strip_suffix(')') else {
+            return;
+        };
+        self.add_parameter(name, type_name);
+    }
+
+    /// `name : type`, or several names sharing a type as in `x, y : float`.
+    fn parse_numpy_parameter(&mut self, entry: &str) {
+        let Some((names, type_name)) = entry.split_once(" : ") else {
+            return;
+        };
+        for name in names.split(',') {
+            self.add_parameter(name, type_name);
+        }
+    }
+
+    /// `:type name: T`, `:param T name: description` and `:rtype: T`.
+    fn parse_sphinx_field(&mut self, field: &str) {
+        let Some((field, value)) = field
+            .strip_prefix(':')
+            .and_then(|field| field.split_once(':'))
+        else {
+            return;
+        };
+        let mut words = field.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("type"), Some(name), None) => self.add_parameter(name, value),
+            (Some("param" | "parameter" | "arg" | "argument"), Some(type_name), Some(name)) => {
+                self.add_parameter(name, type_name);
+            }
+            (Some("rtype"), None, None) => {
+                self.returns = normalize_type(value).into_iter().collect();
+            }
+            _ => {}
+        }
+    }
+
+    fn add_parameter(&mut self, name: &str, type_name: &str) {
+        let name = name.trim().trim_start_matches('*');
+        if let Some(type_name) = normalize_type(type_name) {
+            if !name.is_empty() {
+                self.parameters.insert(name.to_owned(), type_name);
+            }
+        }
+    }
+
+    /// The documented type of a parameter.
+    #[must_use]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.get(name).map(String::as_str)
+    }
+
+    /// The documented return type; several NumPy return values make a tuple.
+    #[must_use]
+    pub fn returns(&self) -> Option<String> {
+        match self.returns.as_slice() {
+            [] => None,
+            [single] => Some(single.clone()),
+            several => Some(format!("tuple[{}]", several.join(", "))),
+        }
+    }
+}
+
+/// Turn a documented type into an annotation, e.g. `int, optional` into `int` and
+/// `str or None` into `str | None`. Returns `None` for prose that does not read as a type.
+fn normalize_type(type_name: &str) -> Option<String> {
+    let type_name = type_name.trim();
+    let type_name = type_name
+        .strip_suffix("optional")
+        .map_or(type_name, |rest| rest.trim_end().trim_end_matches(','))
+        .trim()
+        .replace(" or ", " | ");
+
+    let is_type = !type_name.is_empty()
+        && type_name
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_.[](), |'\"".contains(c))
+        && !type_name
+            .replace(", ", ",")
+            .replace(" | ", "|")
+            .contains(' ');
+    is_type.then_some(type_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_google_style() {
+        let types = DocstringTypes::parse(
+            "Move the point.
+
+            Args:
+                dx (int): Horizontal offset.
+                dy (float, optional): Vertical offset,
+                    which defaults to zero.
+                label: Untyped.
+                *extra (str): More.
+
+            Returns:
+                Point: The moved point.
+                    Chainable: with other calls.
+
+            Raises:
+                ValueError (str): Never typed.
+            ",
+        );
+        assert_eq!(types.parameter("dx"), Some("int"));
+        assert_eq!(types.parameter("dy"), Some("float"));
+        assert_eq!(types.parameter("label"), None);
+        assert_eq!(types.parameter("extra"), Some("str"));
+        assert_eq!(types.parameter("ValueError"), None);
+        assert_eq!(types.returns().as_deref(), Some("Point"));
+    }
+
+    #[test]
+    fn test_numpy_style() {
+        let types = DocstringTypes::parse(
+            "Scale the vector.
+
+            Parameters
+            ----------
+            x, y : float
+                Factors.
+            mode : str or None, optional
+                Rounding mode.
+
+            Returns
+            -------
+            width : int
+                The width.
+            height : int
+                The height.
+            ",
+        );
+        assert_eq!(types.parameter("x"), Some("float"));
+        assert_eq!(types.parameter("y"), Some("float"));
+        assert_eq!(types.parameter("mode"), Some("str | None"));
+        assert_eq!(types.returns().as_deref(), Some("tuple[int, int]"));
+    }
+
+    #[test]
+    fn test_sphinx_style() {
+        let types = DocstringTypes::parse(
+            "Open the file.
+
+            :param path: Where to read.
+            :type path: str
+            :param int mode: The mode.
+            :returns: The handle.
+            :rtype: IO[bytes]
+            ",
+        );
+        assert_eq!(types.parameter("path"), Some("str"));
+        assert_eq!(types.parameter("mode"), Some("int"));
+        assert_eq!(types.returns().as_deref(), Some("IO[bytes]"));
+    }
+
+    #[test]
+    fn test_normalize_type() {
+        assert_eq!(
+            normalize_type("dict[str, int]").as_deref(),
+            Some("dict[str, int]")
+        );
+        assert_eq!(normalize_type("int, optional").as_deref(), Some("int"));
+        assert_eq!(normalize_type("True if it worked"), None);
+    }
+}
@@ -0,0 +1,84 @@
+/// Enum classes and their members: which class attributes become members, and the values
+/// they are assigned
+use super::checker::Checker;
+use crate::ast;
+use crate::render::renderer::ClassType;
+use ruff_python_ast::Expr;
+
+/// Returns the class type of an enum deriving from the given `enum` base class, e.g.
+/// `ClassType::Flag` for `enum.IntFlag`.
+pub fn enum_class_type(segments: &[&str]) -> Option<ClassType> {
+    match segments {
+        ["enum", "Enum" | "ReprEnum"] => Some(ClassType::Enumeration),
+        ["enum", "IntEnum"] => Some(ClassType::IntEnum),
+        ["enum", "StrEnum"] => Some(ClassType::StrEnum),
+        ["enum", "Flag" | "IntFlag"] => Some(ClassType::Flag),
+        _ => None,
+    }
+}
+
+/// Returns true for `_sunder_` names, which are reserved for enum configuration such as
+/// `_ignore_` and `_order_`.
+pub fn is_sunder(name: &str) -> bool {
+    name.len() > 2 && name.starts_with('_') && name.ends_with('_') && !name.starts_with("__")
+}
+
+/// Whether an attribute of this name can be an enum member: dunder, sunder and name-mangled
+/// (`__private`) names are ordinary class attributes.
+pub fn is_member_name(name: &str) -> bool {
+    !name.starts_with("__") && !is_sunder(name)
+}
+
+/// Returns the value that makes an assignment an enum member, or `None` if it does not create
+/// one: functions (`lambda`), descriptors such as `property(...)` and values wrapped in
+/// `enum.nonmember(...)`. A value wrapped in `enum.member(...)` is unwrapped.
+pub fn member_value<'a>(value: &'a Expr, checker: &Checker) -> Option<&'a Expr> {
+    let call = match value {
+        Expr::Lambda(_) => return None,
+        Expr::Call(call) => call,
+        _ => return Some(value),
+    };
+    let Some(qualified_name) = checker.semantic().resolve_qualified_name(&call.func) else {
+        return Some(value);
+    };
+    match qualified_name.segments() {
+        ["enum", "member"] => call.arguments.find_positional(0).or(Some(value)),
+        ["enum", "nonmember"]
+        | ["" | "builtins", "property" | "staticmethod" | "classmethod"]
+        | ["functools", "cached_property" | "singledispatchmethod"] => None,
+        _ => Some(value),
+    }
+}
+
+/// The names listed by `_ignore_`, which are removed from the enum class, e.g.
+/// `_ignore_ = ["Period", "i"]` or `_ignore_ = "Period i"`.
+pub fn ignored_names(body: &[ast::Stmt]) -> Vec<&str> {
+    body.iter()
+        .filter_map(|stmt| match stmt {
+            ast::Stmt::Assign(ast::StmtAssign { targets, value, .. })
+                if targets.iter().any(|target| {
+                    target
+                        .as_name_expr()
+                        .is_some_and(|name| name.id.as_str() == "_ignore_")
+                }) =>
+            {
+                Some(value.as_ref())
+            }
+            _ => None,
+        })
+        .flat_map(|value| match value {
+            Expr::StringLiteral(ast::ExprStringLiteral { value, .. }) => value
+                .to_str()
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            Expr::List(ast::ExprList { elts, .. }) | Expr::Tuple(ast::ExprTuple { elts, .. }) => {
+                elts.iter()
+                    .filter_map(Expr::as_string_literal_expr)
+                    .map(|name| name.value.to_str())
+                    .collect()
+            }
+            _ => Vec::new(),
+        })
+        .collect()
+}
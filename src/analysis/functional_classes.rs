@@ -0,0 +1,224 @@
+/// Discovery of classes declared by calling a factory instead of a `class` statement,
+/// e.g. `Point = namedtuple("Point", "x y")` or `Color = Enum("Color", "RED GREEN")`
+use super::enums::enum_class_type;
+use crate::ast;
+use crate::render::renderer::ClassType;
+use ruff_python_ast::Expr;
+use ruff_python_semantic::SemanticModel;
+
+/// The factories that declare a class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassFactory {
+    /// `collections.namedtuple("Point", "x y")`
+    NamedTuple,
+    /// `typing.NamedTuple("Point", [("x", int), ("y", int)])`
+    TypedNamedTuple,
+    /// `typing.TypedDict("Movie", {"title": str}, total=False)`
+    TypedDict,
+    /// `enum.Enum("Color", "RED GREEN")`, with the class type of the enum called
+    Enum(ClassType),
+    /// `typing.NewType("UserId", int)`
+    NewType,
+}
+
+impl ClassFactory {
+    /// Returns the factory called by the given call expression, if any.
+    pub fn from_call(call: &ast::ExprCall, semantic: &SemanticModel) -> Option<Self> {
+        let qualified_name = semantic.resolve_qualified_name(&call.func)?;
+        match qualified_name.segments() {
+            ["collections", "namedtuple"] => Some(Self::NamedTuple),
+            ["typing" | "typing_extensions", "NamedTuple"] => Some(Self::TypedNamedTuple),
+            ["typing" | "typing_extensions", "TypedDict"] => Some(Self::TypedDict),
+            ["enum", "Enum" | "IntEnum" | "StrEnum" | "Flag" | "IntFlag"] => {
+                enum_class_type(qualified_name.segments()).map(Self::Enum)
+            }
+            ["typing" | "typing_extensions", "NewType"] => Some(Self::NewType),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn class_type(self) -> ClassType {
+        match self {
+            Self::NamedTuple | Self::TypedNamedTuple => ClassType::NamedTuple,
+            Self::TypedDict => ClassType::TypedDict,
+            Self::Enum(class_type) => class_type,
+            Self::NewType => ClassType::NewType,
+        }
+    }
+}
+
+/// A field (or enum member) declared through the factory's arguments
+#[derive(Debug, Clone)]
+pub struct FunctionalField<'a> {
+    pub name: String,
+    /// Declared type, for `typing.NamedTuple` and `TypedDict` fields
+    pub annotation: Option<&'a Expr>,
+    /// Explicit value, for enum members declared as pairs or a mapping
+    pub value: Option<&'a Expr>,
+}
+
+/// A class declared by calling one of the [`ClassFactory`] functions
+#[derive(Debug, Clone)]
+pub struct FunctionalClass<'a> {
+    pub factory: ClassFactory,
+    pub fields: Vec<FunctionalField<'a>>,
+    /// `total=` of a `TypedDict` (true unless given as `False`)
+    pub total: bool,
+    /// Supertype of a `NewType`
+    pub supertype: Option<&'a Expr>,
+}
+
+impl<'a> FunctionalClass<'a> {
+    /// Parse the call to a class factory, or return `None` if it is not one.
+    pub fn from_call(call: &'a ast::ExprCall, semantic: &SemanticModel) -> Option<Self> {
+        let factory = ClassFactory::from_call(call, semantic)?;
+        let arguments = &call.arguments;
+
+        let mut class = Self {
+            factory,
+            fields: Vec::new(),
+            total: true,
+            supertype: None,
+        };
+
+        match factory {
+            ClassFactory::NamedTuple => {
+                if let Some(field_names) = arguments.find_argument_value("field_names", 1) {
+                    class.fields = names(field_names);
+                }
+            }
+            ClassFactory::TypedNamedTuple => {
+                if let Some(fields) = arguments.find_positional(1) {
+                    class.fields = typed_fields(fields);
+                } else {
+                    // Keyword syntax: `NamedTuple("Point", x=int, y=int)`
+                    class.fields = arguments
+                        .keywords
+                        .iter()
+                        .filter_map(|keyword| {
+                            Some(FunctionalField {
+                                name: keyword.arg.as_ref()?.to_string(),
+                                annotation: Some(&keyword.value),
+                                value: None,
+                            })
+                        })
+                        .collect();
+                }
+            }
+            ClassFactory::TypedDict => {
+                if let Some(fields) = arguments.find_positional(1) {
+                    class.fields = typed_fields(fields);
+                }
+                class.total = arguments
+                    .find_keyword("total")
+                    .is_none_or(|keyword| is_total(&keyword.value));
+            }
+            ClassFactory::Enum(_) => {
+                if let Some(members) = arguments.find_argument_value("names", 1) {
+                    class.fields = pairs(members).map_or_else(
+                        || names(members),
+                        |pairs| {
+                            pairs
+                                .into_iter()
+                                .map(|(name, value)| FunctionalField {
+                                    name: name.to_owned(),
+                                    annotation: None,
+                                    value: Some(value),
+                                })
+                                .collect()
+                        },
+                    );
+                }
+            }
+            ClassFactory::NewType => {
+                class.supertype = arguments.find_argument_value("tp", 1);
+            }
+        }
+
+        Some(class)
+    }
+}
+
+/// Whether a `total=` argument leaves the keys of a `TypedDict` required.
+#[must_use]
+pub fn is_total(value: &Expr) -> bool {
+    !matches!(
+        value,
+        Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: false, .. })
+    )
+}
+
+/// Field names given as one string (`"x y"` or `"x, y"`) or a sequence of strings.
+fn names(expr: &Expr) -> Vec<FunctionalField<'_>> {
+    let untyped = |name: &str| FunctionalField {
+        name: name.to_owned(),
+        annotation: None,
+        value: None,
+    };
+
+    match expr {
+        Expr::StringLiteral(ast::ExprStringLiteral { value, .. }) => value
+            .to_str()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .map(untyped)
+            .collect(),
+        Expr::List(ast::ExprList { elts, .. }) | Expr::Tuple(ast::ExprTuple { elts, .. }) => elts
+            .iter()
+            .filter_map(Expr::as_string_literal_expr)
+            .map(|name| untyped(name.value.to_str()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Fields given as `(name, type)` pairs or a `{name: type}` mapping.
+fn typed_fields(expr: &Expr) -> Vec<FunctionalField<'_>> {
+    pairs(expr)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, annotation)| FunctionalField {
+            name: name.to_owned(),
+            annotation: Some(annotation),
+            value: None,
+        })
+        .collect()
+}
+
+/// Returns the `(name, value)` pairs of a `{"name": value}` mapping or a sequence of
+/// `("name", value)` tuples, or `None` if the expression is neither.
+fn pairs(expr: &Expr) -> Option<Vec<(&str, &Expr)>> {
+    match expr {
+        Expr::Dict(ast::ExprDict { items, .. }) => Some(
+            items
+                .iter()
+                .filter_map(|item| {
+                    let name = item.key.as_ref()?.as_string_literal_expr()?;
+                    Some((name.value.to_str(), &item.value))
+                })
+                .collect(),
+        ),
+        Expr::List(ast::ExprList { elts, .. }) | Expr::Tuple(ast::ExprTuple { elts, .. })
+            if elts
+                .first()
+                .is_some_and(|elt| !elt.is_string_literal_expr()) =>
+        {
+            Some(
+                elts.iter()
+                    .filter_map(|elt| match elt {
+                        Expr::Tuple(ast::ExprTuple { elts: pair, .. })
+                        | Expr::List(ast::ExprList { elts: pair, .. }) => match pair.as_slice() {
+                            [name, value] => {
+                                Some((name.as_string_literal_expr()?.value.to_str(), value))
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,209 @@
+/// Discovery of instance attributes assigned through the method receiver (e.g. `self.x = ...`)
+use crate::ast;
+use ruff_python_ast::{Expr, Stmt};
+use ruff_python_semantic::analyze::visibility::{is_classmethod, is_staticmethod};
+use ruff_python_semantic::SemanticModel;
+
+/// An attribute assignment on the method receiver found somewhere in a method body.
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceAttribute<'a> {
+    pub name: &'a str,
+    /// Explicit annotation, as in `self.x: int = 0`
+    pub annotation: Option<&'a Expr>,
+    /// The assigned value, when it can be paired with the target
+    pub value: Option<&'a Expr>,
+}
+
+/// Returns the instance attributes a class declares through `__slots__`, e.g.
+/// `__slots__ = ("x", "y")`. A single string names one slot and a mapping's keys are the slots
+/// (its values are docstrings). The `__dict__` and `__weakref__` slots are not attributes.
+pub fn slot_names(body: &[Stmt]) -> Vec<&str> {
+    let mut slots = Vec::new();
+    for stmt in body {
+        let (target, value) = match stmt {
+            Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                let [target] = targets.as_slice() else {
+                    continue;
+                };
+                (target, value.as_ref())
+            }
+            Stmt::AnnAssign(ast::StmtAnnAssign {
+                target,
+                value: Some(value),
+                ..
+            }) => (target.as_ref(), value.as_ref()),
+            _ => continue,
+        };
+        if !target
+            .as_name_expr()
+            .is_some_and(|name| name.id.as_str() == "__slots__")
+        {
+            continue;
+        }
+
+        let names: Vec<&Expr> = match value {
+            Expr::StringLiteral(_) => vec![value],
+            Expr::Tuple(ast::ExprTuple { elts, .. })
+            | Expr::List(ast::ExprList { elts, .. })
+            | Expr::Set(ast::ExprSet { elts, .. }) => elts.iter().collect(),
+            Expr::Dict(ast::ExprDict { items, .. }) => {
+                items.iter().filter_map(|item| item.key.as_ref()).collect()
+            }
+            _ => continue,
+        };
+        slots.extend(
+            names
+                .into_iter()
+                .filter_map(Expr::as_string_literal_expr)
+                .map(|name| name.value.to_str())
+                .filter(|name| !matches!(*name, "__dict__" | "__weakref__")),
+        );
+    }
+    slots
+}
+
+/// Returns the name of the receiver parameter (`self` by convention) of a method.
+/// Static methods and class methods have no instance receiver.
+fn receiver_name<'a>(
+    function: &'a ast::StmtFunctionDef,
+    semantic: &SemanticModel,
+) -> Option<&'a str> {
+    if is_staticmethod(&function.decorator_list, semantic)
+        || is_classmethod(&function.decorator_list, semantic)
+    {
+        return None;
+    }
+
+    function
+        .parameters
+        .posonlyargs
+        .iter()
+        .chain(&function.parameters.args)
+        .next()
+        .map(|parameter| parameter.parameter.name.as_str())
+}
+
+/// Collect every `self.<name> = ...` and `self.<name>: T = ...` assignment in a method body,
+/// including those nested in control flow. Nested functions and classes are not searched.
+pub fn collect_instance_attributes<'a>(
+    function: &'a ast::StmtFunctionDef,
+    semantic: &SemanticModel,
+) -> Vec<InstanceAttribute<'a>> {
+    let mut found = Vec::new();
+    if let Some(receiver) = receiver_name(function, semantic) {
+        visit_body(&function.body, receiver, &mut found);
+    }
+    found
+}
+
+fn visit_body<'a>(body: &'a [Stmt], receiver: &str, found: &mut Vec<InstanceAttribute<'a>>) {
+    for stmt in body {
+        visit_stmt(stmt, receiver, found);
+    }
+}
+
+fn visit_stmt<'a>(stmt: &'a Stmt, receiver: &str, found: &mut Vec<InstanceAttribute<'a>>) {
+    match stmt {
+        Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+            for target in targets {
+                visit_target(target, Some(value.as_ref()), receiver, found);
+            }
+        }
+        Stmt::AnnAssign(ast::StmtAnnAssign {
+            target,
+            annotation,
+            value,
+            ..
+        }) => {
+            if let Some(name) = receiver_attribute(target, receiver) {
+                found.push(InstanceAttribute {
+                    name,
+                    annotation: Some(annotation.as_ref()),
+                    value: value.as_deref(),
+                });
+            }
+        }
+        Stmt::If(ast::StmtIf {
+            body,
+            elif_else_clauses,
+            ..
+        }) => {
+            visit_body(body, receiver, found);
+            for clause in elif_else_clauses {
+                visit_body(&clause.body, receiver, found);
+            }
+        }
+        Stmt::For(ast::StmtFor { body, orelse, .. })
+        | Stmt::While(ast::StmtWhile { body, orelse, .. }) => {
+            visit_body(body, receiver, found);
+            visit_body(orelse, receiver, found);
+        }
+        Stmt::With(ast::StmtWith { body, .. }) => visit_body(body, receiver, found),
+        Stmt::Try(ast::StmtTry {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+            ..
+        }) => {
+            visit_body(body, receiver, found);
+            for handler in handlers {
+                let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                visit_body(&handler.body, receiver, found);
+            }
+            visit_body(orelse, receiver, found);
+            visit_body(finalbody, receiver, found);
+        }
+        Stmt::Match(ast::StmtMatch { cases, .. }) => {
+            for case in cases {
+                visit_body(&case.body, receiver, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn visit_target<'a>(
+    target: &'a Expr,
+    value: Option<&'a Expr>,
+    receiver: &str,
+    found: &mut Vec<InstanceAttribute<'a>>,
+) {
+    match target {
+        // Tuple unpacking: `self.a, self.b = a, b`
+        Expr::Tuple(ast::ExprTuple { elts: targets, .. })
+        | Expr::List(ast::ExprList { elts: targets, .. }) => {
+            let values = match value {
+                Some(
+                    Expr::Tuple(ast::ExprTuple { elts: values, .. })
+                    | Expr::List(ast::ExprList { elts: values, .. }),
+                ) if values.len() == targets.len() => Some(values),
+                _ => None,
+            };
+            for (idx, target) in targets.iter().enumerate() {
+                let value = values.map(|values| &values[idx]);
+                visit_target(target, value, receiver, found);
+            }
+        }
+        _ => {
+            if let Some(name) = receiver_attribute(target, receiver) {
+                found.push(InstanceAttribute {
+                    name,
+                    annotation: None,
+                    value,
+                });
+            }
+        }
+    }
+}
+
+/// Returns `x` if the expression is `<receiver>.x`.
+fn receiver_attribute<'a>(expr: &'a Expr, receiver: &str) -> Option<&'a str> {
+    let Expr::Attribute(ast::ExprAttribute { value, attr, .. }) = expr else {
+        return None;
+    };
+    match value.as_ref() {
+        Expr::Name(ast::ExprName { id, .. }) if id.as_str() == receiver => Some(attr.as_str()),
+        _ => None,
+    }
+}
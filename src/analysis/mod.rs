@@ -1,5 +1,13 @@
 pub mod checker;
 pub mod class_helpers;
 pub mod class_type_detector;
+pub mod django;
+pub mod docstring_types;
+pub mod enums;
+pub mod functional_classes;
+pub mod instance_attributes;
+pub mod model_fields;
 pub mod parameter_generator;
+pub mod return_inference;
+pub mod sqlalchemy;
 pub mod type_analyzer;
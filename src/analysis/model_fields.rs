@@ -0,0 +1,205 @@
+/// Extraction of field defaults and options from model-like classes
+/// (dataclasses, Pydantic models, attrs classes and msgspec structs)
+use super::checker::Checker;
+use super::class_helpers::decorator_function;
+use crate::ast;
+use crate::render::renderer::ClassType;
+use ruff_python_ast::Expr;
+
+/// Options that apply to every field of a model class, e.g. `@dataclass(frozen=True)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModelOptions {
+    pub frozen: bool,
+    pub kw_only: bool,
+}
+
+impl ModelOptions {
+    /// Read the options from the class keywords (`class M(BaseModel, frozen=True)`), the
+    /// decorator arguments (`@define(kw_only=True)`), frozen decorators (`@attrs.frozen`) and
+    /// the Pydantic `model_config`.
+    pub fn from_class(class: &ast::StmtClassDef, class_type: ClassType, checker: &Checker) -> Self {
+        let mut options = Self::default();
+
+        let decorator_keywords = class
+            .decorator_list
+            .iter()
+            .filter_map(|decorator| decorator.expression.as_call_expr())
+            .flat_map(|call| call.arguments.keywords.iter());
+        for keyword in class.keywords().iter().chain(decorator_keywords) {
+            options.set(keyword.arg.as_deref(), &keyword.value);
+        }
+
+        if class_type == ClassType::AttrsClass
+            && class.decorator_list.iter().any(|decorator| {
+                checker
+                    .semantic()
+                    .resolve_qualified_name(decorator_function(decorator))
+                    .is_some_and(|name| matches!(name.segments(), ["attr" | "attrs", "frozen"]))
+            })
+        {
+            options.frozen = true;
+        }
+
+        if class_type == ClassType::PydanticModel {
+            for stmt in &class.body {
+                if let Some(config) = pydantic_model_config(stmt) {
+                    options.set_from_config(config);
+                }
+            }
+        }
+
+        options
+    }
+
+    fn set(&mut self, name: Option<&str>, value: &Expr) {
+        let enabled = matches!(
+            value,
+            Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: true, .. })
+        );
+        match name {
+            Some("frozen") => self.frozen |= enabled,
+            Some("kw_only") => self.kw_only |= enabled,
+            _ => {}
+        }
+    }
+
+    /// Read `ConfigDict(frozen=True)` or `{"frozen": True}`.
+    fn set_from_config(&mut self, config: &Expr) {
+        match config {
+            Expr::Call(call) => {
+                for keyword in &call.arguments.keywords {
+                    self.set(keyword.arg.as_deref(), &keyword.value);
+                }
+            }
+            Expr::Dict(ast::ExprDict { items, .. }) => {
+                for item in items {
+                    let name = item
+                        .key
+                        .as_ref()
+                        .and_then(Expr::as_string_literal_expr)
+                        .map(|name| name.value.to_str());
+                    self.set(name, &item.value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the value assigned to `model_config` if the statement assigns it.
+pub fn pydantic_model_config(stmt: &ast::Stmt) -> Option<&Expr> {
+    let (target, value) = match stmt {
+        ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => (targets.first()?, value),
+        ast::Stmt::AnnAssign(ast::StmtAnnAssign {
+            target,
+            value: Some(value),
+            ..
+        }) => (target.as_ref(), value),
+        _ => return None,
+    };
+    target
+        .as_name_expr()
+        .is_some_and(|name| name.id.as_str() == "model_config")
+        .then_some(value.as_ref())
+}
+
+/// The default and options of a model field, read from the value assigned to it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldDetails {
+    pub default: Option<String>,
+    pub modifiers: Vec<String>,
+}
+
+impl FieldDetails {
+    /// Read a plain default (`x: int = 0`) or the arguments of a field specifier such as
+    /// `Field(default=0, alias="X")` or `attrs.field(factory=list, kw_only=True)`, then add
+    /// the options that the class sets for all of its fields.
+    pub fn from_value(value: Option<&Expr>, options: ModelOptions, checker: &Checker) -> Self {
+        let mut details = Self::default();
+
+        match value {
+            Some(Expr::Call(call)) if is_field_specifier(call, checker) => {
+                details.read_specifier(call, checker);
+            }
+            Some(value) => details.default = Some(checker.generator().expr(value)),
+            None => {}
+        }
+
+        for (enabled, modifier) in [(options.frozen, "frozen"), (options.kw_only, "kw_only")] {
+            if enabled
+                && !details
+                    .modifiers
+                    .iter()
+                    .any(|existing| existing == modifier)
+            {
+                details.modifiers.push(modifier.to_owned());
+            }
+        }
+
+        details
+    }
+
+    fn read_specifier(&mut self, call: &ast::ExprCall, checker: &Checker) {
+        let arguments = &call.arguments;
+
+        // `Field(...)` marks a required Pydantic field
+        self.default = arguments
+            .find_argument_value("default", 0)
+            .filter(|default| !default.is_ellipsis_literal_expr())
+            .map(|default| checker.generator().expr(default))
+            .or_else(|| {
+                arguments
+                    .find_keyword("default_factory")
+                    .or_else(|| arguments.find_keyword("factory"))
+                    .map(|factory| format!("{}()", checker.generator().expr(&factory.value)))
+            });
+
+        // msgspec calls the alias `name`
+        if let Some(alias) = arguments
+            .find_keyword("alias")
+            .or_else(|| arguments.find_keyword("name"))
+        {
+            self.modifiers
+                .push(format!("alias={}", checker.generator().expr(&alias.value)));
+        }
+
+        let is_true = |name: &str| {
+            arguments.find_keyword(name).is_some_and(|keyword| {
+                matches!(
+                    keyword.value,
+                    Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: true, .. })
+                )
+            })
+        };
+        // attrs freezes a single field with `on_setattr=attrs.setters.frozen`
+        let is_frozen_on_setattr = arguments
+            .find_keyword("on_setattr")
+            .and_then(|keyword| checker.semantic().resolve_qualified_name(&keyword.value))
+            .is_some_and(|name| matches!(name.segments(), ["attr" | "attrs", "setters", "frozen"]));
+        if is_true("frozen") || is_frozen_on_setattr {
+            self.modifiers.push("frozen".to_owned());
+        }
+        if is_true("kw_only") {
+            self.modifiers.push("kw_only".to_owned());
+        }
+    }
+}
+
+/// Whether the call creates a field with options, e.g. `dataclasses.field(...)` or
+/// `pydantic.Field(...)`.
+pub fn is_field_specifier(call: &ast::ExprCall, checker: &Checker) -> bool {
+    checker
+        .semantic()
+        .resolve_qualified_name(&call.func)
+        .is_some_and(|name| {
+            matches!(
+                name.segments(),
+                ["dataclasses" | "msgspec", "field"]
+                    | ["pydantic", "Field"]
+                    | ["pydantic", "fields", "Field"]
+                    | ["attr", "ib" | "attrib" | "field"]
+                    | ["attrs", "field"]
+                    | ["sqlmodel", "Field"]
+            )
+        })
+}
@@ -1,9 +1,14 @@
-use ruff_python_ast::{Identifier, Parameter, ParameterWithDefault, Parameters};
+use super::checker::Checker;
+use crate::render::renderer::{MethodParameter, ParameterKind};
+use ruff_python_ast::{Expr, Parameter, ParameterWithDefault, Parameters};
 
 /// A trimmed-down version of the Ruff Generator,
-/// but only for generating the parameters of a function.
+/// but only for collecting the parameters of a function.
+///
+/// Annotations and defaults are unparsed eagerly so the renderer can decide
+/// how much of each parameter to show.
 pub struct ParameterGenerator {
-    buffer: String,
+    parameters: Vec<MethodParameter>,
 }
 
 impl Default for ParameterGenerator {
@@ -16,68 +21,70 @@ impl ParameterGenerator {
     #[must_use]
     pub const fn new() -> Self {
         Self {
-            buffer: String::new(),
+            parameters: Vec::new(),
         }
     }
 
-    fn p(&mut self, s: &str) {
-        self.buffer.push_str(s);
-    }
-
-    fn p_id(&mut self, s: &Identifier) {
-        self.p(s.as_str());
-    }
-
-    fn p_if(&mut self, cond: bool, s: &str) {
-        if cond {
-            self.p(s);
-        }
-    }
-
-    fn p_delim(&mut self, first: &mut bool, s: &str) {
-        self.p_if(!core::mem::take(first), s);
-    }
-
-    pub fn unparse_parameters(&mut self, parameters: &Parameters) {
-        let mut first = true;
-        for (i, parameter_with_default) in parameters
-            .posonlyargs
-            .iter()
-            .chain(&parameters.args)
-            .enumerate()
-        {
-            self.p_delim(&mut first, ", ");
-            self.unparse_parameter_with_default(parameter_with_default);
-            self.p_if(i + 1 == parameters.posonlyargs.len(), ", /");
+    pub fn unparse_parameters(&mut self, parameters: &Parameters, checker: &Checker) {
+        for parameter_with_default in &parameters.posonlyargs {
+            self.unparse_parameter_with_default(
+                parameter_with_default,
+                ParameterKind::PositionalOnly,
+                checker,
+            );
         }
-        if parameters.vararg.is_some() || !parameters.kwonlyargs.is_empty() {
-            self.p_delim(&mut first, ", ");
-            self.p("*");
+        for parameter_with_default in &parameters.args {
+            self.unparse_parameter_with_default(
+                parameter_with_default,
+                ParameterKind::PositionalOrKeyword,
+                checker,
+            );
         }
         if let Some(vararg) = &parameters.vararg {
-            self.unparse_parameter(vararg);
+            self.unparse_parameter(vararg, ParameterKind::VarPositional, None, checker);
         }
         for kwarg in &parameters.kwonlyargs {
-            self.p_delim(&mut first, ", ");
-            self.unparse_parameter_with_default(kwarg);
+            self.unparse_parameter_with_default(kwarg, ParameterKind::KeywordOnly, checker);
         }
         if let Some(kwarg) = &parameters.kwarg {
-            self.p_delim(&mut first, ", ");
-            self.p("**");
-            self.unparse_parameter(kwarg);
+            self.unparse_parameter(kwarg, ParameterKind::VarKeyword, None, checker);
         }
     }
 
-    fn unparse_parameter(&mut self, parameter: &Parameter) {
-        self.p_id(&parameter.name);
+    fn unparse_parameter(
+        &mut self,
+        parameter: &Parameter,
+        kind: ParameterKind,
+        default: Option<&Expr>,
+        checker: &Checker,
+    ) {
+        self.parameters.push(MethodParameter {
+            name: parameter.name.to_string(),
+            kind,
+            type_annotation: parameter
+                .annotation
+                .as_deref()
+                .map(|annotation| checker.generator().expr(annotation)),
+            default: default.map(|default| checker.generator().expr(default)),
+        });
     }
 
-    fn unparse_parameter_with_default(&mut self, parameter_with_default: &ParameterWithDefault) {
-        self.unparse_parameter(&parameter_with_default.parameter);
+    fn unparse_parameter_with_default(
+        &mut self,
+        parameter_with_default: &ParameterWithDefault,
+        kind: ParameterKind,
+        checker: &Checker,
+    ) {
+        self.unparse_parameter(
+            &parameter_with_default.parameter,
+            kind,
+            parameter_with_default.default.as_deref(),
+            checker,
+        );
     }
 
     #[must_use]
-    pub fn generate(self) -> String {
-        self.buffer
+    pub fn generate(self) -> Vec<MethodParameter> {
+        self.parameters
     }
 }
@@ -0,0 +1,258 @@
+/// Lightweight inference of the return type of an unannotated function from its body: the
+/// literals and constructor calls it returns, `return self`, and whether it is a generator
+use super::checker::Checker;
+use super::type_analyzer;
+use crate::ast;
+use ruff_python_ast::visitor::{walk_expr, walk_stmt, Visitor};
+use ruff_python_ast::{Expr, Stmt};
+use ruff_python_semantic::analyze::visibility::{is_classmethod, is_staticmethod};
+
+/// Builtin types whose call constructs an instance of the type itself, e.g. `dict(a=1)`
+const BUILTIN_CONSTRUCTORS: &[&str] = &[
+    "bool",
+    "bytearray",
+    "bytes",
+    "complex",
+    "dict",
+    "float",
+    "frozenset",
+    "int",
+    "list",
+    "object",
+    "set",
+    "str",
+    "tuple",
+];
+
+/// The first parameter of a method, which receives the instance (`self`) or, in a class
+/// method, the class (`cls`), whatever it is called
+#[derive(Clone, Copy)]
+struct Receiver<'a> {
+    name: &'a str,
+    is_class: bool,
+}
+
+/// The `return` statements and `yield` expressions of a function body, leaving out those of
+/// nested functions, lambdas and classes.
+#[derive(Default)]
+struct ExitVisitor<'a> {
+    returns: Vec<Option<&'a Expr>>,
+    yields: Vec<Option<&'a Expr>>,
+    /// A `yield from`, whose yielded type is not known
+    delegates: bool,
+}
+
+impl<'a> Visitor<'a> for ExitVisitor<'a> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(_) | Stmt::ClassDef(_) => {}
+            Stmt::Return(ast::StmtReturn { value, .. }) => {
+                self.returns.push(value.as_deref());
+                walk_stmt(self, stmt);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Lambda(_) => {}
+            Expr::Yield(ast::ExprYield { value, .. }) => {
+                self.yields.push(value.as_deref());
+                walk_expr(self, expr);
+            }
+            Expr::YieldFrom(_) => {
+                self.delegates = true;
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+/// Whether a body does nothing but stand in for an implementation: a docstring, `...`,
+/// `pass` or a `raise`, as in abstract methods and protocols.
+fn is_placeholder(body: &[Stmt]) -> bool {
+    body.iter().all(|stmt| match stmt {
+        Stmt::Pass(_) | Stmt::Raise(_) => true,
+        Stmt::Expr(ast::StmtExpr { value, .. }) => {
+            matches!(
+                value.as_ref(),
+                Expr::EllipsisLiteral(_) | Expr::StringLiteral(_)
+            )
+        }
+        _ => false,
+    })
+}
+
+/// Whether every path through the body ends in a `return` or `raise`.
+fn always_exits(body: &[Stmt]) -> bool {
+    match body.last() {
+        Some(Stmt::Return(_) | Stmt::Raise(_)) => true,
+        Some(Stmt::If(ast::StmtIf {
+            body,
+            elif_else_clauses,
+            ..
+        })) => {
+            elif_else_clauses
+                .last()
+                .is_some_and(|clause| clause.test.is_none())
+                && always_exits(body)
+                && elif_else_clauses
+                    .iter()
+                    .all(|clause| always_exits(&clause.body))
+        }
+        Some(Stmt::With(ast::StmtWith { body, .. })) => always_exits(body),
+        _ => false,
+    }
+}
+
+/// The type of a returned or yielded value, if it is a literal, a constructor call, or the
+/// receiver.
+fn value_type(
+    value: &Expr,
+    class_name: &str,
+    receiver: Option<Receiver>,
+    checker: &Checker,
+) -> Option<String> {
+    match value {
+        Expr::Name(ast::ExprName { id, .. })
+            if receiver.is_some_and(|receiver| receiver.name == id.as_str()) =>
+        {
+            Some(if receiver.is_some_and(|receiver| receiver.is_class) {
+                format!("type[{class_name}]")
+            } else {
+                class_name.to_owned()
+            })
+        }
+        Expr::Call(ast::ExprCall { func, .. }) => {
+            constructed_type(func, class_name, receiver, checker)
+        }
+        Expr::Compare(_)
+        | Expr::UnaryOp(ast::ExprUnaryOp {
+            op: ast::UnaryOp::Not,
+            ..
+        }) => Some("bool".to_owned()),
+        Expr::UnaryOp(ast::ExprUnaryOp { operand, .. }) => {
+            value_type(operand, class_name, receiver, checker)
+        }
+        // Operators and boolean expressions may produce anything
+        Expr::BinOp(_) | Expr::BoolOp(_) | Expr::EllipsisLiteral(_) => None,
+        _ => type_analyzer::infer_literal_type(value).map(str::to_owned),
+    }
+}
+
+/// The class instantiated by calling `func`: a builtin type (`dict(...)`), a class defined in
+/// this module, or `cls(...)` in a class method. Imported callables may be factories rather
+/// than classes, so calls to them are not inferred.
+fn constructed_type(
+    func: &Expr,
+    class_name: &str,
+    receiver: Option<Receiver>,
+    checker: &Checker,
+) -> Option<String> {
+    if let Expr::Name(ast::ExprName { id, .. }) = func {
+        if let Some(receiver) = receiver.filter(|receiver| receiver.name == id.as_str()) {
+            return receiver.is_class.then(|| class_name.to_owned());
+        }
+        if checker.class_id_for_name(id.as_str()).is_some() {
+            return Some(id.to_string());
+        }
+    }
+
+    let qualified_name = checker.semantic().resolve_qualified_name(func)?;
+    match qualified_name.segments() {
+        ["" | "builtins", name] if BUILTIN_CONSTRUCTORS.contains(name) => Some((*name).to_owned()),
+        _ => None,
+    }
+}
+
+/// Join the inferred types into a union, in order of appearance and without repeats.
+fn union(types: Vec<String>) -> String {
+    let mut unique: Vec<String> = Vec::with_capacity(types.len());
+    for type_name in types {
+        if !unique.contains(&type_name) {
+            unique.push(type_name);
+        }
+    }
+    unique.join(" | ")
+}
+
+/// Infer the return type of an unannotated method of `class_name`, or return `None` if any of
+/// its exits has a type that cannot be told from the source alone.
+///
+/// # Examples
+/// - no `return` → `None`
+/// - `return 0` and a bare `return` → `int | None`
+/// - `return self` or `return cls(...)` → the class
+/// - `return cls` in a class method → `type[...]` of the class
+/// - `yield 1` → `Iterator[int]`, and `AsyncIterator[int]` in an `async def`
+/// - `async def` returning `"done"` → `Awaitable[str]`
+pub fn infer_return_type(
+    function: &ast::StmtFunctionDef,
+    class_name: &str,
+    checker: &Checker,
+) -> Option<String> {
+    if is_placeholder(&function.body) {
+        return None;
+    }
+
+    let semantic = checker.semantic();
+    let receiver = function
+        .parameters
+        .posonlyargs
+        .iter()
+        .chain(&function.parameters.args)
+        .next()
+        .filter(|_| !is_staticmethod(&function.decorator_list, semantic))
+        .map(|parameter| Receiver {
+            name: parameter.parameter.name.as_str(),
+            is_class: is_classmethod(&function.decorator_list, semantic),
+        });
+    let type_of = |value: Option<&Expr>| {
+        value.map_or_else(
+            || Some("None".to_owned()),
+            |value| value_type(value, class_name, receiver, checker),
+        )
+    };
+
+    let mut visitor = ExitVisitor::default();
+    visitor.visit_body(&function.body);
+
+    if !visitor.yields.is_empty() || visitor.delegates {
+        let yielded = if visitor.delegates {
+            None
+        } else {
+            visitor
+                .yields
+                .into_iter()
+                .map(type_of)
+                .collect::<Option<Vec<_>>>()
+        };
+        let iterator = if function.is_async {
+            "AsyncIterator"
+        } else {
+            "Iterator"
+        };
+        return Some(format!(
+            "{iterator}[{}]",
+            yielded.map_or_else(|| "Any".to_owned(), union)
+        ));
+    }
+
+    let mut returned: Vec<String> = visitor
+        .returns
+        .into_iter()
+        .map(type_of)
+        .collect::<Option<_>>()?;
+    if !always_exits(&function.body) {
+        returned.push("None".to_owned());
+    }
+    let returned = union(returned);
+
+    Some(if function.is_async {
+        format!("Awaitable[{returned}]")
+    } else {
+        returned
+    })
+}
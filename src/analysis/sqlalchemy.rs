@@ -0,0 +1,229 @@
+/// Extraction of SQLAlchemy declarative mappings: `Mapped[...]` annotations, columns declared
+/// with `mapped_column()` or `Column()`, and relationships declared with `relationship()`
+use super::checker::Checker;
+use crate::ast;
+use crate::render::renderer::Cardinality;
+use ruff_python_ast::Expr;
+
+/// Class attributes that configure the mapping rather than declare a column
+pub const MAPPER_CONFIGURATION: &[&str] = &[
+    "__tablename__",
+    "__table__",
+    "__table_args__",
+    "__mapper_args__",
+    "__abstract__",
+    "type_annotation_map",
+    "metadata",
+    "registry",
+];
+
+/// Returns true if the qualified name is a declarative base class, e.g.
+/// `sqlalchemy.orm.DeclarativeBase`.
+pub fn is_declarative_base(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        [
+            "sqlalchemy",
+            "orm",
+            "DeclarativeBase" | "DeclarativeBaseNoMeta"
+        ] | [
+            "sqlalchemy",
+            "orm",
+            "decl_api",
+            "DeclarativeBase" | "DeclarativeBaseNoMeta"
+        ]
+    )
+}
+
+/// Returns true if the qualified name is a function returning a declarative base class, e.g.
+/// `sqlalchemy.orm.declarative_base`.
+pub fn is_declarative_base_factory(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        ["sqlalchemy", "orm", "declarative_base"]
+            | ["sqlalchemy", "orm", "decl_api", "declarative_base"]
+            | ["sqlalchemy", "ext", "declarative", "declarative_base"]
+    )
+}
+
+/// Unwrap `Mapped[T]` to `T`; other annotations are returned unchanged.
+pub fn unwrap_mapped<'a>(annotation: &'a Expr, checker: &Checker) -> &'a Expr {
+    let Expr::Subscript(subscript) = annotation else {
+        return annotation;
+    };
+    let is_mapped = checker
+        .semantic()
+        .resolve_qualified_name(&subscript.value)
+        .is_some_and(|name| {
+            matches!(
+                name.segments(),
+                ["sqlalchemy", "orm", "Mapped"] | ["sqlalchemy", "orm", "base", "Mapped"]
+            )
+        });
+    if is_mapped {
+        subscript.slice.as_ref()
+    } else {
+        annotation
+    }
+}
+
+/// A column declared by `mapped_column(...)` or `Column(...)`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MappedColumn {
+    /// Python type of the column's SQL type argument, e.g. `int` for `Integer`
+    pub value_type: Option<&'static str>,
+    pub primary_key: bool,
+    /// Column referenced by `ForeignKey("users.id")`
+    pub foreign_key: Option<String>,
+    pub default: Option<String>,
+}
+
+impl MappedColumn {
+    /// Parse a call to `mapped_column` or `Column`, or return `None` if the call is
+    /// something else.
+    pub fn from_call(call: &ast::ExprCall, checker: &Checker) -> Option<Self> {
+        let qualified_name = checker.semantic().resolve_qualified_name(&call.func)?;
+        if !matches!(
+            qualified_name.segments(),
+            ["sqlalchemy", "orm", "mapped_column"]
+                | ["sqlalchemy", "Column"]
+                | ["sqlalchemy", "schema" | "sql", "Column"]
+                | ["sqlalchemy", "sql", "schema", "Column"]
+        ) {
+            return None;
+        }
+
+        let arguments = &call.arguments;
+        let mut column = Self {
+            primary_key: arguments
+                .find_keyword("primary_key")
+                .is_some_and(|keyword| {
+                    matches!(
+                        keyword.value,
+                        Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: true, .. })
+                    )
+                }),
+            default: arguments
+                .find_keyword("default")
+                .map(|keyword| checker.generator().expr(&keyword.value)),
+            ..Self::default()
+        };
+
+        // The SQL type and the foreign key are positional, after an optional column name
+        for arg in &arguments.args {
+            let (callee, argument) = match arg {
+                Expr::Call(call) => (call.func.as_ref(), call.arguments.find_positional(0)),
+                arg => (arg, None),
+            };
+            let Some(name) = checker.semantic().resolve_qualified_name(callee) else {
+                continue;
+            };
+            match name.segments() {
+                ["sqlalchemy", "ForeignKey"] | ["sqlalchemy", "schema" | "sql", "ForeignKey"] => {
+                    column.foreign_key = argument
+                        .and_then(Expr::as_string_literal_expr)
+                        .map(|target| target.value.to_str().to_owned())
+                        .or_else(|| argument.map(|target| checker.generator().expr(target)));
+                }
+                ["sqlalchemy", sql_type] | ["sqlalchemy", "types", sql_type] => {
+                    column.value_type = value_type(sql_type);
+                }
+                _ => {}
+            }
+        }
+
+        Some(column)
+    }
+
+    /// Key markers shown next to the attribute, e.g. `PK` or `FK users.id`.
+    #[must_use]
+    pub fn modifiers(&self) -> Vec<String> {
+        let mut modifiers = Vec::new();
+        if self.primary_key {
+            modifiers.push("PK".to_owned());
+        }
+        if let Some(foreign_key) = &self.foreign_key {
+            modifiers.push(format!("FK {foreign_key}"));
+        }
+        modifiers
+    }
+}
+
+/// A relationship declared by `relationship(...)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relationship {
+    /// Related class given as the first argument (`relationship("Child")`), which an
+    /// annotated relationship may omit
+    pub target: Option<String>,
+    /// Attribute of the related class holding the reverse side
+    pub back_populates: Option<String>,
+    /// Cardinality implied by the arguments alone, for relationships without a `Mapped`
+    /// annotation: `uselist=False` holds at most one object, anything else a collection
+    pub cardinality: Cardinality,
+}
+
+impl Relationship {
+    /// Parse a call to `relationship`, or return `None` if the call is something else.
+    pub fn from_call(call: &ast::ExprCall, checker: &Checker) -> Option<Self> {
+        let qualified_name = checker.semantic().resolve_qualified_name(&call.func)?;
+        if !matches!(
+            qualified_name.segments(),
+            ["sqlalchemy", "orm", "relationship"]
+                | ["sqlalchemy", "orm", "relationships", "relationship"]
+        ) {
+            return None;
+        }
+
+        let arguments = &call.arguments;
+        let target =
+            arguments
+                .find_argument_value("argument", 0)
+                .and_then(|argument| match argument {
+                    Expr::StringLiteral(ast::ExprStringLiteral { value, .. }) => {
+                        Some(value.to_str().to_owned())
+                    }
+                    Expr::Name(ast::ExprName { id, .. }) => Some(id.to_string()),
+                    _ => None,
+                });
+        let back_populates = arguments
+            .find_keyword("back_populates")
+            .and_then(|keyword| keyword.value.as_string_literal_expr())
+            .map(|name| name.value.to_str().to_owned());
+        let uselist = arguments.find_keyword("uselist").is_none_or(|keyword| {
+            !matches!(
+                keyword.value,
+                Expr::BooleanLiteral(ast::ExprBooleanLiteral { value: false, .. })
+            )
+        });
+
+        Some(Self {
+            target,
+            back_populates,
+            cardinality: if uselist {
+                Cardinality::Many
+            } else {
+                Cardinality::ZeroOrOne
+            },
+        })
+    }
+}
+
+/// The Python type stored by a SQLAlchemy column type, if it is a known one.
+fn value_type(sql_type: &str) -> Option<&'static str> {
+    let value_type = match sql_type {
+        "Integer" | "BigInteger" | "SmallInteger" | "INTEGER" | "BIGINT" | "SMALLINT" => "int",
+        "String" | "Text" | "Unicode" | "UnicodeText" | "VARCHAR" | "CHAR" | "TEXT" => "str",
+        "Boolean" | "BOOLEAN" => "bool",
+        "Float" | "Double" | "REAL" | "FLOAT" => "float",
+        "Numeric" | "NUMERIC" | "DECIMAL" => "Decimal",
+        "Date" | "DATE" => "date",
+        "DateTime" | "DATETIME" | "TIMESTAMP" => "datetime",
+        "Time" | "TIME" => "time",
+        "Interval" => "timedelta",
+        "LargeBinary" | "BLOB" => "bytes",
+        "Uuid" | "UUID" => "UUID",
+        "JSON" => "Any",
+        _ => return None,
+    };
+    Some(value_type)
+}
@@ -1,24 +1,136 @@
 /// Type analysis utilities for extracting and analyzing Python types from AST
 use super::checker::Checker;
-use ruff_python_ast::name::QualifiedName;
-use ruff_python_ast::Expr;
+use crate::render::renderer::{Cardinality, CompositionKind};
+use ruff_python_ast::name::{QualifiedName, UnqualifiedName};
+use ruff_python_ast::{Expr, Number, Operator};
+use ruff_python_parser::parse_expression;
 
 // Built-in Python types that should not be treated as composition relationships
 const BUILTIN_TYPES: &[&str] = &[
     "int", "str", "float", "bool", "bytes", "dict", "list", "tuple", "set", "None",
 ];
 
-/// Extract type names from annotations for composition relationship detection.
-/// Returns zero or more type names that represent potential compositions
-/// (non-builtin, non-typing types).
+/// A class referenced by an annotation, along with how the annotation holds it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompositionType {
+    pub name: String,
+    pub kind: CompositionKind,
+    pub cardinality: Cardinality,
+    /// Key type of a mapping (e.g. `str` for `dict[str, X]`)
+    pub qualifier: Option<String>,
+    /// Attribute of the referenced class that holds the reverse side (SQLAlchemy
+    /// `back_populates`)
+    pub back_populates: Option<String>,
+}
+
+impl CompositionType {
+    /// The same reference held by a field that may be missing (e.g. a `NotRequired` key).
+    #[must_use]
+    pub fn optional(self) -> Self {
+        Self {
+            kind: match self.kind {
+                CompositionKind::Composition => CompositionKind::Aggregation,
+                kind => kind,
+            },
+            cardinality: match self.cardinality {
+                Cardinality::One => Cardinality::ZeroOrOne,
+                cardinality => cardinality,
+            },
+            ..self
+        }
+    }
+
+    /// The same reference held by a class variable, which the class shares rather than owns.
+    #[must_use]
+    pub fn shared(self) -> Self {
+        Self {
+            kind: CompositionKind::Association,
+            ..self
+        }
+    }
+}
+
+/// How the enclosing annotation wraps a type, e.g. `Optional[...]` or `list[...]`
+#[derive(Clone, Copy, Default)]
+struct Holding<'a> {
+    /// Inside `Optional[...]` or a union with `None`
+    optional: bool,
+    /// One of several alternatives of a union
+    alternative: bool,
+    /// Inside a collection or mapping
+    many: bool,
+    /// Key type of the enclosing mapping
+    qualifier: Option<&'a Expr>,
+}
+
+/// Kinds of generic wrappers that determine the edge kind and cardinality
+enum Wrapper {
+    Optional,
+    Union,
+    Collection,
+    Mapping,
+    /// `Literal[...]`, whose strings are values rather than forward references
+    Literal,
+    /// `Annotated[...]`, whose metadata after the type is not part of the type
+    Annotated,
+    Other,
+}
+
+fn classify_wrapper(value: &Expr, checker: &Checker) -> Wrapper {
+    let qualified_name = checker.semantic().resolve_qualified_name(value);
+    let name = match (&qualified_name, value) {
+        (Some(qualified_name), _) => qualified_name.segments().last().copied(),
+        (None, Expr::Name(name)) => Some(name.id.as_str()),
+        _ => None,
+    };
+
+    match name {
+        Some("Optional") => Wrapper::Optional,
+        Some("Union") => Wrapper::Union,
+        Some("Literal") => Wrapper::Literal,
+        Some("Annotated") => Wrapper::Annotated,
+        Some(
+            "list" | "List" | "set" | "Set" | "frozenset" | "FrozenSet" | "tuple" | "Tuple"
+            | "deque" | "Deque" | "Sequence" | "MutableSequence" | "AbstractSet" | "MutableSet"
+            | "Collection" | "Iterable" | "Iterator"
+            // SQLAlchemy collections loaded on demand
+            | "WriteOnlyMapped" | "DynamicMapped",
+        ) => Wrapper::Collection,
+        Some(
+            "dict" | "Dict" | "defaultdict" | "DefaultDict" | "OrderedDict" | "Mapping"
+            | "MutableMapping",
+        ) => Wrapper::Mapping,
+        _ => Wrapper::Other,
+    }
+}
+
+/// Extract the classes referenced by an annotation for composition relationship detection,
+/// along with the edge kind and cardinality implied by the wrappers around them.
+/// Builtin and typing types are skipped.
 ///
 /// # Examples
-/// - `foo: MyClass` → vec!["`MyClass`"]
-/// - `foo: list[MyClass]` → vec!["`MyClass`"]
-/// - `foo: Optional[MyClass]` → vec!["`MyClass`"]
-/// - `foo: X | Y` → vec!["X", "Y"]
-/// - `foo: int` → vec![] (builtin)
-pub fn extract_composition_types(annotation: &Expr, checker: &Checker) -> Vec<String> {
+/// - `foo: MyClass` → `MyClass` composition, "1"
+/// - `foo: Optional[MyClass]` → `MyClass` aggregation, "0..1"
+/// - `foo: list[MyClass]` → `MyClass` aggregation, "*"
+/// - `foo: dict[str, MyClass]` → `MyClass` association qualified by `str`, "*"
+/// - `foo: X | Y` → `X` and `Y` compositions, "0..1" each
+/// - `foo: Mapped[list[MyClass]]` → `MyClass` aggregation, "*"
+/// - `foo: "Optional[MyClass]"` → `MyClass` aggregation, "0..1"
+/// - `foo: Annotated[MyClass, Meta()]` → `MyClass` composition, "1"
+/// - `foo: dict[str, list[MyClass]]` → `MyClass` association qualified by `str`, "*"
+/// - `foo: int` → nothing (builtin)
+pub fn extract_composition_types(annotation: &Expr, checker: &Checker) -> Vec<CompositionType> {
+    let mut out = Vec::new();
+    collect_composition_types(annotation, checker, Holding::default(), &mut out);
+    out
+}
+
+fn collect_composition_types<'a>(
+    annotation: &'a Expr,
+    checker: &Checker,
+    holding: Holding<'a>,
+    out: &mut Vec<CompositionType>,
+) {
     fn is_eligible_name(type_name: &str, annotation: &Expr, checker: &Checker) -> Option<String> {
         // Skip built-in types
         if BUILTIN_TYPES.contains(&type_name) {
@@ -41,32 +153,258 @@ pub fn extract_composition_types(annotation: &Expr, checker: &Checker) -> Vec<St
 
     match annotation {
         // Simple name: foo: MyClass
-        Expr::Name(name) => is_eligible_name(name.id.as_ref(), annotation, checker)
-            .into_iter()
-            .collect(),
+        Expr::Name(name) => {
+            if let Some(type_name) = is_eligible_name(name.id.as_ref(), annotation, checker) {
+                out.push(held_type(type_name, checker, holding));
+            }
+        }
+
+        // Dotted name: foo: models.MyClass
+        Expr::Attribute(_) => {
+            let Some(dotted) = UnqualifiedName::from_expr(annotation) else {
+                return;
+            };
+            if let Some(type_name) = is_eligible_name(&dotted.to_string(), annotation, checker) {
+                out.push(held_type(type_name, checker, holding));
+            }
+        }
+
+        // Forward reference: foo: "MyClass" or foo: "list[MyClass]"
+        Expr::StringLiteral(string) => {
+            let Ok(parsed) = parse_expression(string.value.to_str()) else {
+                return;
+            };
+            collect_composition_types(parsed.expr(), checker, holding, out);
+        }
 
         // Subscript: foo: list[MyClass], Optional[MyClass], Union[X, Y], etc.
-        Expr::Subscript(subscript) => match subscript.slice.as_ref() {
-            Expr::Name(_) => extract_composition_types(subscript.slice.as_ref(), checker),
-            Expr::Tuple(tuple) => tuple
-                .elts
-                .iter()
-                .flat_map(|elt| extract_composition_types(elt, checker))
-                .collect(),
-            _ => vec![],
-        },
+        Expr::Subscript(subscript) => {
+            let elements = match subscript.slice.as_ref() {
+                Expr::Tuple(tuple) => tuple.elts.as_slice(),
+                slice => std::slice::from_ref(slice),
+            };
+
+            match classify_wrapper(&subscript.value, checker) {
+                Wrapper::Optional => {
+                    let holding = Holding {
+                        optional: true,
+                        ..holding
+                    };
+                    for element in elements {
+                        collect_composition_types(element, checker, holding, out);
+                    }
+                }
+                Wrapper::Union => collect_union_members(elements, checker, holding, out),
+                Wrapper::Mapping if elements.len() == 2 => {
+                    let holding = Holding {
+                        many: true,
+                        qualifier: Some(&elements[0]),
+                        ..holding
+                    };
+                    collect_composition_types(&elements[1], checker, holding, out);
+                }
+                Wrapper::Collection | Wrapper::Mapping => {
+                    let holding = Holding {
+                        many: true,
+                        ..holding
+                    };
+                    for element in elements {
+                        collect_composition_types(element, checker, holding, out);
+                    }
+                }
+                Wrapper::Literal => {}
+                Wrapper::Annotated => {
+                    if let Some(element) = elements.first() {
+                        collect_composition_types(element, checker, holding, out);
+                    }
+                }
+                Wrapper::Other => {
+                    for element in elements {
+                        collect_composition_types(element, checker, holding, out);
+                    }
+                }
+            }
+        }
 
         // Binary op for union types (X | Y)
-        Expr::BinOp(binop) => {
-            let mut out = extract_composition_types(binop.left.as_ref(), checker);
-            out.extend(extract_composition_types(binop.right.as_ref(), checker));
-            out
+        Expr::BinOp(_) => {
+            let mut members = Vec::new();
+            flatten_union(annotation, &mut members);
+            collect_union_members(members, checker, holding, out);
+        }
+
+        _ => {}
+    }
+}
+
+/// The reference to a class, with the edge kind and cardinality implied by its holding.
+fn held_type(name: String, checker: &Checker, holding: Holding) -> CompositionType {
+    let kind = if holding.qualifier.is_some() {
+        CompositionKind::Association
+    } else if holding.many || holding.optional {
+        CompositionKind::Aggregation
+    } else {
+        CompositionKind::Composition
+    };
+    let cardinality = if holding.many {
+        Cardinality::Many
+    } else if holding.optional || holding.alternative {
+        Cardinality::ZeroOrOne
+    } else {
+        Cardinality::One
+    };
+    CompositionType {
+        name,
+        kind,
+        cardinality,
+        qualifier: holding
+            .qualifier
+            .map(|qualifier| checker.generator().expr(qualifier)),
+        back_populates: None,
+    }
+}
+
+/// Collect `X | Y | None` operands into a flat list.
+fn flatten_union<'a>(expr: &'a Expr, members: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinOp(binop) if binop.op == Operator::BitOr => {
+            flatten_union(&binop.left, members);
+            flatten_union(&binop.right, members);
         }
+        _ => members.push(expr),
+    }
+}
 
-        _ => vec![],
+/// Collect the members of a union; `None` makes the others optional, and several members make
+/// each of them one alternative.
+fn collect_union_members<'a>(
+    members: impl IntoIterator<Item = &'a Expr>,
+    checker: &Checker,
+    holding: Holding<'a>,
+    out: &mut Vec<CompositionType>,
+) {
+    let (nones, types): (Vec<&Expr>, Vec<&Expr>) = members
+        .into_iter()
+        .partition(|member| member.is_none_literal_expr());
+    let holding = Holding {
+        optional: holding.optional || !nones.is_empty(),
+        alternative: holding.alternative || types.len() > 1,
+        ..holding
+    };
+    for member in types {
+        collect_composition_types(member, checker, holding, out);
     }
 }
 
+/// Unwrap the annotation of a `TypedDict` key, returning the key's type and whether the key
+/// is required. `Required[...]` and `NotRequired[...]` override the class's `total=`.
+///
+/// # Examples
+/// - `int` in a `total=False` class → (`int`, false)
+/// - `Required[int]` in a `total=False` class → (`int`, true)
+/// - `NotRequired[int]` → (`int`, false)
+pub fn typed_dict_key<'a>(
+    annotation: &'a Expr,
+    total: bool,
+    checker: &Checker,
+) -> (&'a Expr, bool) {
+    let Expr::Subscript(subscript) = annotation else {
+        return (annotation, total);
+    };
+    let qualified_name = checker.semantic().resolve_qualified_name(&subscript.value);
+    let name = match (&qualified_name, subscript.value.as_ref()) {
+        (Some(qualified_name), _) => qualified_name.segments().last().copied(),
+        (None, Expr::Name(name)) => Some(name.id.as_str()),
+        _ => None,
+    };
+
+    match name {
+        Some("Required") => (subscript.slice.as_ref(), true),
+        Some("NotRequired") => (subscript.slice.as_ref(), false),
+        _ => (annotation, total),
+    }
+}
+
+/// Qualifiers wrapped around the type of a class-level annotation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Qualifiers {
+    /// `ClassVar[...]`
+    pub class_var: bool,
+    /// `Final[...]`
+    pub is_final: bool,
+    /// `dataclasses.InitVar[...]`
+    pub init_var: bool,
+}
+
+/// Strip the `ClassVar`, `Final` and `InitVar` qualifiers from a class-level annotation,
+/// returning the declared type and the qualifiers found. A bare `Final` or `ClassVar`
+/// declares no type.
+///
+/// # Examples
+/// - `ClassVar[int]` → (Some(`int`), class variable)
+/// - `ClassVar[Final[int]]` → (Some(`int`), final class variable)
+/// - `Final` → (None, final)
+/// - `list[int]` → (Some(`list[int]`), none)
+pub fn strip_qualifiers<'a>(
+    annotation: &'a Expr,
+    checker: &Checker,
+) -> (Option<&'a Expr>, Qualifiers) {
+    let mut qualifiers = Qualifiers::default();
+    let mut declared = annotation;
+    loop {
+        let (value, slice) = match declared {
+            Expr::Subscript(subscript) => {
+                (subscript.value.as_ref(), Some(subscript.slice.as_ref()))
+            }
+            declared => (declared, None),
+        };
+        let Some(qualified_name) = checker.semantic().resolve_qualified_name(value) else {
+            break;
+        };
+        match qualified_name.segments() {
+            ["typing" | "typing_extensions", "ClassVar"] => qualifiers.class_var = true,
+            ["typing" | "typing_extensions", "Final"] => qualifiers.is_final = true,
+            ["dataclasses", "InitVar"] => qualifiers.init_var = true,
+            _ => break,
+        }
+        match slice {
+            Some(slice) => declared = slice,
+            None => return (None, qualifiers),
+        }
+    }
+    (Some(declared), qualifiers)
+}
+
+/// Infer the type of a value from its literal syntax, for unannotated assignments.
+///
+/// # Examples
+/// - `1` → Some("int")
+/// - `"text"` → Some("str")
+/// - `[1, 2]` → Some("list")
+/// - `compute()` → None
+pub fn infer_literal_type(value: &Expr) -> Option<&'static str> {
+    let value_type = match value {
+        Expr::BoolOp(_) | Expr::BooleanLiteral(_) => "bool",
+        Expr::BinOp(_) | Expr::UnaryOp(_) => "int",
+        Expr::Lambda(_) => "Callable",
+        Expr::DictComp(_) | Expr::Dict(_) => "dict",
+        Expr::Set(_) | Expr::SetComp(_) => "set",
+        Expr::FString(_) | Expr::StringLiteral(_) => "str",
+        Expr::NoneLiteral(_) => "None",
+        Expr::BytesLiteral(_) => "bytes",
+        Expr::EllipsisLiteral(_) => "...",
+        Expr::ListComp(_) | Expr::List(_) => "list",
+        Expr::Tuple(_) => "tuple",
+        Expr::NumberLiteral(inner) => match inner.value {
+            Number::Int(_) => "int",
+            Number::Float(_) => "float",
+            Number::Complex { .. } => "complex",
+        },
+        _ => return None,
+    };
+    Some(value_type)
+}
+
 /// Extract generic type parameters from a base class expression.
 /// Returns the type parameter(s) if the base is Generic[T] or similar.
 ///
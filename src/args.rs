@@ -1,7 +1,11 @@
 use clap::Parser;
 
+use pymermaider_wasm::render::mermaid_renderer::RenderOptions;
 use pymermaider_wasm::render::output_format::OutputFormat;
-use pymermaider_wasm::render::renderer::DiagramDirection;
+use pymermaider_wasm::render::renderer::{
+    DiagramDirection, DiagramKind, DocstringNotes, OverloadStyle, ParameterStyle, Visibility,
+    VisibilityPolicy,
+};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -62,6 +66,20 @@ pub struct Args {
     )]
     pub include: Option<Vec<String>>,
 
+    /// Analyze only `.pyi` stub files, e.g. to diagram a stub-only (`types-*`) package.
+    #[arg(
+        long,
+        verbatim_doc_comment,
+        default_value = "false",
+        help_heading = "File selection"
+    )]
+    pub stubs_only: bool,
+
+    /// Kind of diagram to render: a UML class diagram, or an entity-relationship diagram of the
+    /// classes' attributes and associations (class, er).
+    #[arg(long, verbatim_doc_comment, default_value = "class")]
+    pub diagram: DiagramKind,
+
     /// Class diagram direction (TB, BT, LR, RL).
     #[arg(long, verbatim_doc_comment, default_value = "TB")]
     pub direction: DiagramDirection,
@@ -70,7 +88,97 @@ pub struct Args {
     #[arg(long, verbatim_doc_comment, default_value = "false")]
     pub no_title: bool,
 
-    /// Hide private members (fields and methods with names starting with _) from the diagram.
+    /// Hide private members from the diagram. Shorthand for `--min-visibility protected`.
     #[arg(long, verbatim_doc_comment, default_value = "false")]
     pub hide_private_members: bool,
+
+    /// Hide members less visible than this (public, protected, private).
+    #[arg(long, verbatim_doc_comment, default_value = "private")]
+    pub min_visibility: Visibility,
+
+    /// How leading underscores map to member visibility (underscore, mangling).
+    /// `underscore`: `_name` and `__name` are private.
+    /// `mangling`: `_name` is protected and name-mangled `__name` is private.
+    #[arg(long, verbatim_doc_comment, default_value = "underscore")]
+    pub visibility_policy: VisibilityPolicy,
+
+    /// How much of each method parameter to show (names, types, full).
+    #[arg(long, verbatim_doc_comment, default_value = "names")]
+    pub parameter_style: ParameterStyle,
+
+    /// Omit the receiver (self/cls) from method signatures.
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub hide_self: bool,
+
+    /// How to show the `@overload` variants of a method (collapse, expand).
+    /// `collapse`: one entry with the union of the overloads' types, if their parameters match.
+    /// `expand`: each overload with its full typed signature, without the implementation.
+    #[arg(long, verbatim_doc_comment, default_value = "collapse")]
+    pub overload_style: OverloadStyle,
+
+    /// Show the values of enum members, e.g. `RED = 1` or `GREEN = auto()`.
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub show_enum_values: bool,
+
+    /// Show return types inferred from the bodies of unannotated methods, marked with a
+    /// trailing `?` (e.g. `build(self) Widget?`).
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub infer_return_types: bool,
+
+    /// Take the types of unannotated parameters and return values from Google, NumPy or
+    /// Sphinx style docstrings. Annotations always take precedence.
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub docstring_types: bool,
+
+    /// Attach class docstrings to the diagram as notes (none, line, paragraph).
+    /// `line`: the first line of the docstring.
+    /// `paragraph`: the first paragraph, up to the first blank line.
+    #[arg(long, verbatim_doc_comment, default_value = "none")]
+    pub docstrings: DocstringNotes,
+
+    /// Cut docstring notes longer than this many characters (0 for no limit).
+    #[arg(long, verbatim_doc_comment, default_value = "120")]
+    pub docstring_max_length: usize,
+
+    /// Break docstring notes into lines of at most this many characters (0 for one line).
+    #[arg(long, verbatim_doc_comment, default_value = "40")]
+    pub docstring_line_length: usize,
+
+    /// Merge edges between the same pair of classes into one edge labelled with all the fields
+    /// that create it, instead of drawing one edge per field.
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub merge_parallel_edges: bool,
+
+    /// Draw dependency edges (..>) to the classes that methods accept as parameters or return.
+    #[arg(long, verbatim_doc_comment, default_value = "false")]
+    pub show_dependencies: bool,
+}
+
+impl Args {
+    /// Build the diagram render options from the command-line flags.
+    pub const fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            direction: self.direction,
+            min_visibility: if self.hide_private_members
+                && !self.min_visibility.is_at_least(Visibility::Protected)
+            {
+                Visibility::Protected
+            } else {
+                self.min_visibility
+            },
+            visibility_policy: self.visibility_policy,
+            parameter_style: self.parameter_style,
+            hide_self: self.hide_self,
+            overload_style: self.overload_style,
+            show_enum_values: self.show_enum_values,
+            infer_return_types: self.infer_return_types,
+            docstring_types: self.docstring_types,
+            docstrings: self.docstrings,
+            docstring_max_length: self.docstring_max_length,
+            docstring_line_length: self.docstring_line_length,
+            merge_parallel_edges: self.merge_parallel_edges,
+            show_dependencies: self.show_dependencies,
+            diagram_kind: self.diagram,
+        }
+    }
 }
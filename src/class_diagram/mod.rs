@@ -3,25 +3,39 @@ use crate::analysis::class_helpers::{
     is_abc_qualified_name, ClassDefHelpers, QualifiedNameHelpers,
 };
 use crate::analysis::class_type_detector::ClassTypeDetector;
+use crate::analysis::django::{DjangoField, RelatedModel};
+use crate::analysis::docstring_types::{docstring, DocstringTypes};
+use crate::analysis::enums;
+use crate::analysis::functional_classes::{self, ClassFactory, FunctionalClass};
+use crate::analysis::instance_attributes::{
+    collect_instance_attributes, slot_names, InstanceAttribute,
+};
+use crate::analysis::model_fields::{self, FieldDetails, ModelOptions};
 use crate::analysis::parameter_generator::ParameterGenerator;
-use crate::analysis::type_analyzer;
+use crate::analysis::return_inference;
+use crate::analysis::sqlalchemy::{self, MappedColumn, Relationship};
+use crate::analysis::type_analyzer::{self, CompositionType, Qualifiers};
 use crate::ast;
 use crate::render::renderer::{
-    Attribute, ClassNode, CompositionEdge, Diagram, MethodSignature, RelationType,
-    RelationshipEdge, Visibility,
+    Attribute, Cardinality, ClassNode, ClassRef, ClassType, CompositionEdge, CompositionKind,
+    Diagram, MethodSignature, RelationType, RelationshipEdge, Visibility,
 };
-use indexmap::IndexSet;
+use indexmap::map::Entry;
+use indexmap::{IndexMap, IndexSet};
+use log::{debug, warn};
 use ruff_linter::source_kind::SourceKind;
 use ruff_linter::Locator;
 use ruff_python_ast::name::{QualifiedName, UnqualifiedName};
-use ruff_python_ast::{Expr, Number, PySourceType};
+use ruff_python_ast::{Expr, PySourceType};
 use ruff_python_codegen::Stylist;
-use ruff_python_parser::parse_unchecked_source;
+use ruff_python_parser::{parse_unchecked_source, ParseError};
 use ruff_python_semantic::analyze::visibility::{
     is_abstract, is_classmethod, is_final, is_overload, is_override, is_property, is_staticmethod,
 };
 use ruff_python_semantic::{Module, ModuleKind, ModuleSource, SemanticModel};
 use ruff_python_stdlib::typing::simple_magic_return_type;
+use ruff_source_file::LineIndex;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 /// Represents a class member (attribute or method) during processing
@@ -31,11 +45,71 @@ enum ClassMember {
     Method(MethodSignature),
 }
 
+impl ClassMember {
+    fn name(&self) -> &str {
+        match self {
+            Self::Attribute(attr) => &attr.name,
+            Self::Method(method) => &method.name,
+        }
+    }
+}
+
+/// Names declared by a type parameter list (e.g. `T, *Ts, U: int` → `T`, `Ts`, `U`)
+fn type_param_names(type_params: &str) -> Vec<&str> {
+    type_params
+        .split(',')
+        .filter_map(|param| param.split([':', '=']).next())
+        .map(|name| name.trim().trim_start_matches('*'))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Collect the names an assignment target binds, paired with their values where the value
+/// unpacks element by element (e.g. `x, y = 1, "a"` binds `x` to `1` and `y` to `"a"`).
+/// A starred target (`first, *rest = ...`) takes whatever the others leave over.
+fn bind_names<'a>(
+    target: &'a Expr,
+    value: Option<&'a Expr>,
+    bound: &mut Vec<(&'a str, Option<&'a Expr>)>,
+) {
+    match target {
+        Expr::Name(ast::ExprName { id, .. }) => bound.push((id.as_str(), value)),
+        Expr::Tuple(ast::ExprTuple { elts: targets, .. })
+        | Expr::List(ast::ExprList { elts: targets, .. }) => {
+            let values = match value {
+                Some(
+                    Expr::Tuple(ast::ExprTuple { elts: values, .. })
+                    | Expr::List(ast::ExprList { elts: values, .. }),
+                ) => Some(values),
+                _ => None,
+            };
+            let starred = targets.iter().position(Expr::is_starred_expr);
+            for (idx, target) in targets.iter().enumerate() {
+                let value = values.and_then(|values| match starred {
+                    None if values.len() == targets.len() => values.get(idx),
+                    Some(starred) if idx < starred => values.get(idx),
+                    // Targets after the starred one are paired from the end
+                    Some(starred) if idx > starred => values
+                        .len()
+                        .checked_sub(targets.len() - idx)
+                        .map(|idx| &values[idx]),
+                    _ => None,
+                });
+                bind_names(target, value, bound);
+            }
+        }
+        Expr::Starred(ast::ExprStarred { value: target, .. }) => bind_names(target, None, bound),
+        _ => {}
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum BaseKind {
     Skip,
     InheritanceTarget {
         name: String,
+        /// Project-qualified identity of the base, if it resolved to a known class
+        id: Option<String>,
         is_abstract_or_protocol: bool,
     },
 }
@@ -62,8 +136,13 @@ impl ClassDiagram {
         }
     }
 
+    /// Hide private members, or show members of any visibility again.
     pub const fn set_hide_private_members(&mut self, hide: bool) {
-        self.options.hide_private_members = hide;
+        self.options.min_visibility = if hide {
+            Visibility::Protected
+        } else {
+            Visibility::Private
+        };
     }
 
     #[must_use]
@@ -88,7 +167,14 @@ impl ClassDiagram {
             Some(self.path.as_str())
         };
 
-        crate::render::mermaid_renderer::render_diagram(&self.diagram, title, &self.options)
+        match self.options.diagram_kind {
+            DiagramKind::Class => {
+                crate::render::mermaid_renderer::render_diagram(&self.diagram, title, &self.options)
+            }
+            DiagramKind::Er => {
+                crate::render::er_renderer::render_er_diagram(&self.diagram, title, &self.options)
+            }
+        }
     }
 
     pub fn add_class(
@@ -97,7 +183,39 @@ impl ClassDiagram {
         class: &ast::StmtClassDef,
         _indent_level: usize,
     ) {
-        let class_name = class.name.to_string();
+        self.add_class_in_scope(checker, class, None, &HashMap::new(), false);
+    }
+
+    /// Add a class, qualifying its name with the enclosing class (if any).
+    ///
+    /// `enclosing_scope` maps the short names of classes nested in the enclosing class bodies
+    /// to their qualified names, so bases and annotations like `class B(A)` or `a: A` next to a
+    /// nested `class A` resolve.
+    /// Conditional classes (defined under `if`/`try`/`with`) are merged with other definitions
+    /// of the same name instead of being drawn twice.
+    fn add_class_in_scope(
+        &mut self,
+        checker: &Checker,
+        class: &ast::StmtClassDef,
+        enclosing_class: Option<&str>,
+        enclosing_scope: &HashMap<String, String>,
+        is_conditional: bool,
+    ) {
+        let class_name = enclosing_class.map_or_else(
+            || class.name.to_string(),
+            |outer| format!("{outer}.{}", class.name),
+        );
+
+        // Nested classes visible from this class body by short name: those of the enclosing
+        // classes, shadowed by the ones nested directly in this class
+        let mut scope = enclosing_scope.clone();
+        scope.extend(class.body.iter().filter_map(|stmt| match stmt {
+            ast::Stmt::ClassDef(nested) => Some((
+                nested.name.to_string(),
+                format!("{class_name}.{}", nested.name),
+            )),
+            _ => None,
+        }));
 
         // Find generic type parameters - either from explicit [T] syntax or Generic[T] bases
         let generic_type_var = class.type_params.as_ref().map_or_else(
@@ -125,46 +243,238 @@ impl ClassDiagram {
             },
         );
 
-        // Detect composition relationships from class attributes
-        let mut composition_types: IndexSet<String> = IndexSet::new();
+        // Detect class type using ClassTypeDetector
+        let detector = ClassTypeDetector::new(checker);
+        let class_type = detector.detect_type(class);
+        let class_is_enum = class.is_enum(checker.semantic());
+
+        // Subclasses of the Pydantic models, msgspec structs and Django models seen so far are
+        // of the same kind
+        let class_type = if class_type == ClassType::Regular {
+            class
+                .bases()
+                .iter()
+                .find_map(|base| {
+                    match self.classify_base(checker, &detector, base, class_is_enum) {
+                        BaseKind::InheritanceTarget { name, id, .. } => self
+                            .diagram
+                            .inherited_class_type(id.as_deref().unwrap_or(&name)),
+                        BaseKind::Skip => None,
+                    }
+                })
+                .unwrap_or(class_type)
+        } else {
+            class_type
+        };
+
+        // Fields of model-like classes carry defaults and options such as `frozen`
+        let model_options = class_type
+            .is_model()
+            .then(|| ModelOptions::from_class(class, class_type, checker));
+
+        // Keys of a `TypedDict` may be optional, depending on `total=` and their annotation
+        let typed_dict_total = (class_type == ClassType::TypedDict).then(|| {
+            class
+                .arguments
+                .as_deref()
+                .and_then(|arguments| arguments.find_keyword("total"))
+                .is_none_or(|keyword| functional_classes::is_total(&keyword.value))
+        });
+
+        // Detect composition relationships from class attributes, keyed by field name
+        let mut composition_types: IndexSet<(String, CompositionType)> = IndexSet::new();
         for stmt in &class.body {
-            if let ast::Stmt::AnnAssign(ast::StmtAnnAssign { annotation, .. }) = stmt {
-                composition_types.extend(type_analyzer::extract_composition_types(
-                    annotation.as_ref(),
+            if let ast::Stmt::AnnAssign(ast::StmtAnnAssign {
+                target, annotation, ..
+            }) = stmt
+            {
+                let field = checker.generator().expr(target);
+                let (annotation, required) = typed_dict_total
+                    .map_or((annotation.as_ref(), true), |total| {
+                        type_analyzer::typed_dict_key(annotation, total, checker)
+                    });
+                // An `InitVar` is only passed to `__init__`, and a `ClassVar` is shared by the
+                // class rather than owned by each instance
+                let (Some(declared), qualifiers) =
+                    type_analyzer::strip_qualifiers(annotation, checker)
+                else {
+                    continue;
+                };
+                if qualifiers.init_var {
+                    continue;
+                }
+                composition_types.extend(
+                    type_analyzer::extract_composition_types(declared, checker)
+                        .into_iter()
+                        .map(|comp_type| {
+                            let comp_type = if required {
+                                comp_type
+                            } else {
+                                comp_type.optional()
+                            };
+                            let comp_type = if qualifiers.class_var {
+                                comp_type.shared()
+                            } else {
+                                comp_type
+                            };
+                            (field.clone(), comp_type)
+                        }),
+                );
+            }
+        }
+
+        // Fields declared by a factory base, e.g. `class Point(namedtuple("Point", "x y"))`
+        let mut attributes = Vec::new();
+        for base in class.bases() {
+            if let Some(functional) = base
+                .as_call_expr()
+                .and_then(|call| FunctionalClass::from_call(call, checker.semantic()))
+            {
+                attributes.extend(Self::functional_attributes(
                     checker,
+                    &functional,
+                    &mut composition_types,
                 ));
             }
         }
 
         // Process class body statements
+        let ignored_enum_names = if class_type.is_enum() {
+            enums::ignored_names(&class.body)
+        } else {
+            Vec::new()
+        };
         let mut members: IndexSet<ClassMember> = IndexSet::new();
         for stmt in &class.body {
-            if let Some(member) = Self::process_stmt_to_member(checker, stmt) {
+            // Pydantic configuration is not a field
+            if class_type == ClassType::PydanticModel
+                && model_fields::pydantic_model_config(stmt).is_some()
+            {
+                continue;
+            }
+            // Neither is the configuration of a SQLAlchemy mapping, e.g. `__tablename__`
+            if class_type == ClassType::SqlAlchemyModel
+                && Self::assigned_name(stmt)
+                    .is_some_and(|name| sqlalchemy::MAPPER_CONFIGURATION.contains(&name))
+            {
+                continue;
+            }
+
+            // Nor is an enum's configuration (e.g. `_ignore_`), or the names it removes
+            if class_type.is_enum()
+                && Self::assigned_name(stmt).is_some_and(|name| {
+                    enums::is_sunder(name) || ignored_enum_names.contains(&name)
+                })
+            {
+                continue;
+            }
+
+            // `__slots__` declares instance attributes, collected with the others below
+            if Self::assigned_name(stmt) == Some("__slots__") {
+                continue;
+            }
+            // `x += 1` rebinds an attribute declared earlier rather than declaring a new one
+            if matches!(stmt, ast::Stmt::AugAssign(_))
+                && Self::assigned_name(stmt)
+                    .is_some_and(|name| members.iter().any(|member| member.name() == name))
+            {
+                continue;
+            }
+
+            let stmt_members = match (stmt, typed_dict_total) {
+                (ast::Stmt::AnnAssign(ann_assign), Some(total)) => {
+                    Self::typed_dict_key_member(checker, ann_assign, total)
+                        .into_iter()
+                        .collect()
+                }
+                (ast::Stmt::Assign(assign), _) if class_type == ClassType::DjangoModel => {
+                    Self::django_field_member(checker, assign, &class_name, &mut composition_types)
+                        .map_or_else(
+                            || Self::process_stmt_to_members(checker, stmt),
+                            |member| vec![member],
+                        )
+                }
+                _ if class_type.is_enum() => Self::enum_member(checker, stmt).map_or_else(
+                    || Self::process_stmt_to_members(checker, stmt),
+                    |member| vec![member],
+                ),
+                _ if class_type == ClassType::SqlAlchemyModel => {
+                    Self::sqlalchemy_member(checker, stmt, &mut composition_types).map_or_else(
+                        || Self::process_stmt_to_members(checker, stmt),
+                        |member| vec![member],
+                    )
+                }
+                _ => Self::process_stmt_to_members(checker, stmt),
+            };
+            for member in stmt_members {
+                let member = match (member, model_options) {
+                    (ClassMember::Attribute(attr), Some(options)) => ClassMember::Attribute(
+                        Self::with_field_details(checker, stmt, attr, options),
+                    ),
+                    (member, _) => member,
+                };
+                let member = match (member, stmt) {
+                    (ClassMember::Method(method), ast::Stmt::FunctionDef(function))
+                        if method.return_type.is_none() && !checker.is_stub() =>
+                    {
+                        ClassMember::Method(MethodSignature {
+                            inferred_return_type: return_inference::infer_return_type(
+                                function,
+                                &class_name,
+                                checker,
+                            ),
+                            ..method
+                        })
+                    }
+                    (member, _) => member,
+                };
                 members.insert(member);
             }
         }
 
-        // Detect class type using ClassTypeDetector
-        let detector = ClassTypeDetector::new(checker);
-        let class_type = detector.detect_type(class);
-        let class_is_enum = class.is_enum(checker.semantic());
+        // Collect instance attributes assigned in method bodies (e.g. `self.x = x` in `__init__`)
+        let instance_attributes = Self::collect_class_instance_attributes(
+            checker,
+            class,
+            &members,
+            &mut composition_types,
+        );
+
+        // The class's own type parameters are not compositions
+        let type_params: Vec<String> = generic_type_var
+            .as_deref()
+            .map(type_param_names)
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        composition_types.retain(|(_, comp_type)| !type_params.contains(&comp_type.name));
 
         // Split members into attributes and methods
-        let mut attributes = Vec::new();
         let mut methods = Vec::new();
         for member in members {
             match member {
-                ClassMember::Attribute(attr) => attributes.push(attr),
+                ClassMember::Attribute(attr) => {
+                    // A field redeclared in the body replaces the one from a factory base
+                    attributes.retain(|existing: &Attribute| existing.name != attr.name);
+                    attributes.push(attr);
+                }
                 ClassMember::Method(method) => methods.push(method),
             }
         }
+        attributes.extend(instance_attributes);
 
+        let class_id = checker.qualify(&class_name);
         let class_node = ClassNode {
+            id: class_id.clone(),
             name: class_name.clone(),
             type_params: generic_type_var,
             class_type,
             attributes,
             methods,
+            is_conditional,
+            is_stub: checker.is_stub(),
+            docstring: docstring(&class.body).map(str::to_owned),
         };
 
         self.diagram.add_class(class_node);
@@ -173,14 +483,23 @@ impl ClassDiagram {
         for base in class.bases() {
             let BaseKind::InheritanceTarget {
                 name,
+                id,
                 is_abstract_or_protocol,
             } = self.classify_base(checker, &detector, base, class_is_enum)
             else {
                 continue;
             };
+            let (name, id) = match enclosing_scope.get(&name) {
+                Some(sibling) => (sibling.clone(), Some(checker.qualify(sibling))),
+                None => (name, id),
+            };
+            let is_abstract_or_protocol = is_abstract_or_protocol
+                || self
+                    .diagram
+                    .is_abstract_or_interface(id.as_deref().unwrap_or(&name));
             let rel = RelationshipEdge {
-                from: class_name.clone(),
-                to: name,
+                from: class_id.clone(),
+                to: ClassRef { id, name },
                 relation_type: if is_abstract_or_protocol {
                     RelationType::Implementation
                 } else {
@@ -191,16 +510,546 @@ impl ClassDiagram {
         }
 
         // Add composition relationships
-        for comp_type in &composition_types {
-            // Extract just the class name (remove module prefix if present)
-            let comp_display = comp_type.split('.').next_back().unwrap_or(comp_type);
-
+        for (field, comp_type) in &composition_types {
             let comp = CompositionEdge {
-                container: class_name.clone(),
-                contained: comp_display.to_string(),
+                container: class_id.clone(),
+                contained: Self::referenced_class(checker, &scope, &comp_type.name),
+                kind: comp_type.kind,
+                cardinality: comp_type.cardinality,
+                qualifier: comp_type.qualifier.clone(),
+                fields: vec![field.clone()],
+                back_populates: comp_type.back_populates.clone(),
+                inverse_cardinality: None,
             };
             self.diagram.add_composition(comp);
         }
+
+        // Dependencies on the types accepted and returned by methods
+        if self.options.show_dependencies {
+            for dependency in Self::collect_dependency_types(checker, class) {
+                if type_params.contains(&dependency) {
+                    continue;
+                }
+                self.diagram.add_relationship(RelationshipEdge {
+                    from: class_id.clone(),
+                    to: Self::referenced_class(checker, &scope, &dependency),
+                    relation_type: RelationType::Dependency,
+                });
+            }
+        }
+
+        // Nested classes become their own nodes, linked back to this class
+        for stmt in &class.body {
+            if let ast::Stmt::ClassDef(nested) = stmt {
+                // Django's `class Meta` holds model options, not a class of the domain
+                if class_type == ClassType::DjangoModel && nested.name.as_str() == "Meta" {
+                    continue;
+                }
+
+                self.add_class_in_scope(checker, nested, Some(&class_name), &scope, is_conditional);
+                self.diagram.add_relationship(RelationshipEdge {
+                    from: checker.qualify(&format!("{class_name}.{}", nested.name)),
+                    to: ClassRef {
+                        id: Some(class_id.clone()),
+                        name: class_name.clone(),
+                    },
+                    relation_type: RelationType::Nesting,
+                });
+            }
+        }
+    }
+
+    /// Add a class declared by calling a factory, e.g. `Point = namedtuple("Point", "x y")`
+    /// or `UserId = NewType("UserId", int)`. Calls to anything else are ignored.
+    fn add_functional_class(
+        &mut self,
+        checker: &Checker,
+        name: &str,
+        call: &ast::ExprCall,
+        is_conditional: bool,
+    ) {
+        let Some(functional) = FunctionalClass::from_call(call, checker.semantic()) else {
+            return;
+        };
+
+        let mut composition_types: IndexSet<(String, CompositionType)> = IndexSet::new();
+        let attributes = Self::functional_attributes(checker, &functional, &mut composition_types);
+
+        let class_id = checker.qualify(name);
+        self.diagram.add_class(ClassNode {
+            id: class_id.clone(),
+            name: name.to_owned(),
+            type_params: None,
+            class_type: functional.factory.class_type(),
+            attributes,
+            methods: Vec::new(),
+            is_conditional,
+            is_stub: checker.is_stub(),
+            docstring: None,
+        });
+
+        // A `NewType` is drawn as a subclass of its supertype
+        if let Some(supertype) = functional.supertype {
+            let detector = ClassTypeDetector::new(checker);
+            if let BaseKind::InheritanceTarget {
+                name,
+                id,
+                is_abstract_or_protocol,
+            } = self.classify_base(checker, &detector, supertype, false)
+            {
+                self.diagram.add_relationship(RelationshipEdge {
+                    from: class_id.clone(),
+                    to: ClassRef { id, name },
+                    relation_type: if is_abstract_or_protocol {
+                        RelationType::Implementation
+                    } else {
+                        RelationType::Inheritance
+                    },
+                });
+            }
+        }
+
+        for (field, comp_type) in composition_types {
+            self.diagram.add_composition(CompositionEdge {
+                container: class_id.clone(),
+                contained: Self::referenced_class(checker, &HashMap::new(), &comp_type.name),
+                kind: comp_type.kind,
+                cardinality: comp_type.cardinality,
+                qualifier: comp_type.qualifier,
+                fields: vec![field],
+                back_populates: comp_type.back_populates,
+                inverse_cardinality: None,
+            });
+        }
+    }
+
+    /// The attributes declared through a class factory's arguments. Typed fields also feed
+    /// composition detection.
+    fn functional_attributes(
+        checker: &Checker,
+        functional: &FunctionalClass,
+        composition_types: &mut IndexSet<(String, CompositionType)>,
+    ) -> Vec<Attribute> {
+        let is_enum = matches!(functional.factory, ClassFactory::Enum(_));
+        functional
+            .fields
+            .iter()
+            .map(|field| {
+                let type_annotation = if let Some(annotation) = field.annotation {
+                    let (annotation, required) = if functional.factory == ClassFactory::TypedDict {
+                        type_analyzer::typed_dict_key(annotation, functional.total, checker)
+                    } else {
+                        (annotation, true)
+                    };
+                    composition_types.extend(
+                        type_analyzer::extract_composition_types(annotation, checker)
+                            .into_iter()
+                            .map(|comp_type| {
+                                let comp_type = if required {
+                                    comp_type
+                                } else {
+                                    comp_type.optional()
+                                };
+                                (field.name.clone(), comp_type)
+                            }),
+                    );
+                    Self::typed_dict_key_type(checker.generator().expr(annotation), required)
+                } else if is_enum {
+                    // Members declared by name alone get `auto()` integer values
+                    field
+                        .value
+                        .map_or(Some("int"), type_analyzer::infer_literal_type)
+                        .unwrap_or("Any")
+                        .to_owned()
+                } else {
+                    "Any".to_owned()
+                };
+
+                Attribute {
+                    default: field
+                        .value
+                        .filter(|_| is_enum)
+                        .map(|value| checker.generator().expr(value)),
+                    is_enum_member: is_enum,
+                    ..Attribute::new(
+                        field.name.clone(),
+                        type_annotation,
+                        checker.visibility_for_name(&field.name),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Add the default and options of a model field to its attribute. Annotated fields and
+    /// unannotated field specifiers (e.g. `x = attr.ib(default=0)`) get details; properties and
+    /// plain class variables are left alone.
+    fn with_field_details(
+        checker: &Checker,
+        stmt: &ast::Stmt,
+        attr: Attribute,
+        options: ModelOptions,
+    ) -> Attribute {
+        let value = match stmt {
+            ast::Stmt::AnnAssign(ast::StmtAnnAssign { value, .. }) => value.as_deref(),
+            ast::Stmt::Assign(ast::StmtAssign { value, .. })
+                if value
+                    .as_call_expr()
+                    .is_some_and(|call| model_fields::is_field_specifier(call, checker)) =>
+            {
+                Some(value.as_ref())
+            }
+            _ => return attr,
+        };
+
+        // Class variables are not fields, so the class's field options do not apply to them
+        let options = if attr.is_static {
+            ModelOptions::default()
+        } else {
+            options
+        };
+        let details = FieldDetails::from_value(value, options, checker);
+        Attribute {
+            default: details.default,
+            modifiers: details.modifiers,
+            ..attr
+        }
+    }
+
+    /// A Django model field assigned in the class body, e.g. `author = models.ForeignKey(...)`.
+    /// Relationship fields also add an association to the related model.
+    fn django_field_member(
+        checker: &Checker,
+        assign: &ast::StmtAssign,
+        class_name: &str,
+        composition_types: &mut IndexSet<(String, CompositionType)>,
+    ) -> Option<ClassMember> {
+        let ([Expr::Name(target)], Expr::Call(call)) =
+            (assign.targets.as_slice(), assign.value.as_ref())
+        else {
+            return None;
+        };
+        let field = DjangoField::from_call(call, checker)?;
+
+        if let Some(relation) = &field.relation {
+            let name = match &relation.model {
+                RelatedModel::SelfRef => class_name.to_owned(),
+                RelatedModel::Named(name) => name.clone(),
+            };
+            composition_types.insert((
+                target.id.to_string(),
+                CompositionType {
+                    name,
+                    kind: CompositionKind::Association,
+                    cardinality: relation.cardinality,
+                    qualifier: None,
+                    back_populates: None,
+                },
+            ));
+        }
+
+        Some(ClassMember::Attribute(Attribute::new(
+            target.id.to_string(),
+            field.type_annotation(class_name),
+            checker.visibility_for_name(target.id.as_str()),
+        )))
+    }
+
+    /// A column or relationship of a SQLAlchemy model, e.g.
+    /// `id: Mapped[int] = mapped_column(primary_key=True)` or
+    /// `children: Mapped[list["Child"]] = relationship(back_populates="parent")`.
+    /// Relationships replace the compositions found in their annotation with associations
+    /// that remember the reverse side.
+    fn sqlalchemy_member(
+        checker: &Checker,
+        stmt: &ast::Stmt,
+        composition_types: &mut IndexSet<(String, CompositionType)>,
+    ) -> Option<ClassMember> {
+        let (target, annotation, value) = match stmt {
+            ast::Stmt::AnnAssign(ast::StmtAnnAssign {
+                target,
+                annotation,
+                value,
+                ..
+            }) => (target.as_ref(), Some(annotation.as_ref()), value.as_deref()),
+            ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                (targets.first()?, None, Some(value.as_ref()))
+            }
+            _ => return None,
+        };
+        let Expr::Name(ast::ExprName { id: target, .. }) = target else {
+            return None;
+        };
+        // Class variables are not mapped
+        if annotation.is_some_and(|annotation| {
+            type_analyzer::strip_qualifiers(annotation, checker).1 != Qualifiers::default()
+        }) {
+            return None;
+        }
+        let name = target.to_string();
+        let call = value.and_then(Expr::as_call_expr);
+
+        let mut attribute = Attribute::new(
+            name.clone(),
+            annotation.map_or_else(
+                || "Any".to_owned(),
+                |annotation| {
+                    checker
+                        .generator()
+                        .expr(sqlalchemy::unwrap_mapped(annotation, checker))
+                },
+            ),
+            checker.visibility_for_name(&name),
+        );
+
+        if let Some(relationship) = call.and_then(|call| Relationship::from_call(call, checker)) {
+            let mut related: Vec<CompositionType> = annotation
+                .map(|annotation| type_analyzer::extract_composition_types(annotation, checker))
+                .unwrap_or_default();
+            if related.is_empty() {
+                if let Some(target) = relationship.target {
+                    if annotation.is_none() {
+                        attribute.type_annotation = match relationship.cardinality {
+                            Cardinality::Many => format!("list[{target}]"),
+                            _ => format!("{target} | None"),
+                        };
+                    }
+                    related.push(CompositionType {
+                        name: target,
+                        kind: CompositionKind::Association,
+                        cardinality: relationship.cardinality,
+                        qualifier: None,
+                        back_populates: None,
+                    });
+                }
+            }
+
+            composition_types.retain(|(field, _)| *field != name);
+            composition_types.extend(related.into_iter().map(|comp_type| {
+                (
+                    name.clone(),
+                    CompositionType {
+                        kind: CompositionKind::Association,
+                        back_populates: relationship.back_populates.clone(),
+                        ..comp_type
+                    },
+                )
+            }));
+        } else if let Some(column) = call.and_then(|call| MappedColumn::from_call(call, checker)) {
+            if annotation.is_none() {
+                attribute.type_annotation = column.value_type.unwrap_or("Any").to_owned();
+            }
+            attribute.modifiers = column.modifiers();
+            attribute.default = column.default;
+        } else if annotation.is_none() {
+            return None;
+        }
+
+        Some(ClassMember::Attribute(attribute))
+    }
+
+    /// The name assigned by a simple class-level assignment, e.g. `__tablename__ = "users"`.
+    fn assigned_name(stmt: &ast::Stmt) -> Option<&str> {
+        let target = match stmt {
+            ast::Stmt::Assign(ast::StmtAssign { targets, .. }) => targets.first()?,
+            ast::Stmt::AnnAssign(ast::StmtAnnAssign { target, .. })
+            | ast::Stmt::AugAssign(ast::StmtAugAssign { target, .. }) => target.as_ref(),
+            _ => return None,
+        };
+        target.as_name_expr().map(|name| name.id.as_str())
+    }
+
+    /// A member of an enum class, e.g. `RED = 1` or `GREEN = auto()`, with its value kept as the
+    /// default. Assignments that do not create a member (e.g. `__str__ = ...` or
+    /// `label = property(...)`) are left to be shown as ordinary attributes.
+    fn enum_member(checker: &Checker, stmt: &ast::Stmt) -> Option<ClassMember> {
+        let (target, value) = match stmt {
+            ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                (targets.first()?, value.as_ref())
+            }
+            ast::Stmt::AnnAssign(ast::StmtAnnAssign {
+                target,
+                value: Some(value),
+                ..
+            }) => (target.as_ref(), value.as_ref()),
+            _ => return None,
+        };
+        let name = target.as_name_expr()?.id.as_str();
+        if !enums::is_member_name(name) {
+            return None;
+        }
+        let value = enums::member_value(value, checker)?;
+
+        Some(ClassMember::Attribute(Attribute {
+            default: Some(checker.generator().expr(value)),
+            is_enum_member: true,
+            ..Attribute::new(
+                name,
+                type_analyzer::infer_literal_type(value).unwrap_or("Any"),
+                checker.visibility_for_name(name),
+            )
+        }))
+    }
+
+    /// A `TypedDict` key declared in the class body, shown as `NotRequired[...]` when it may be
+    /// missing and as its plain type otherwise.
+    fn typed_dict_key_member(
+        checker: &Checker,
+        ann_assign: &ast::StmtAnnAssign,
+        total: bool,
+    ) -> Option<ClassMember> {
+        let Expr::Name(ast::ExprName { id: target, .. }) = ann_assign.target.as_ref() else {
+            return None;
+        };
+
+        let (annotation, required) =
+            type_analyzer::typed_dict_key(&ann_assign.annotation, total, checker);
+        Some(ClassMember::Attribute(Attribute::new(
+            target.to_string(),
+            Self::typed_dict_key_type(checker.generator().expr(annotation), required),
+            checker.visibility_for_name(target.as_str()),
+        )))
+    }
+
+    fn typed_dict_key_type(type_annotation: String, required: bool) -> String {
+        if required {
+            type_annotation
+        } else {
+            format!("NotRequired[{type_annotation}]")
+        }
+    }
+
+    /// Reference a class named in an annotation, preferring classes nested in the current
+    /// class body, then classes defined or imported in the module.
+    fn referenced_class(
+        checker: &Checker,
+        scope: &HashMap<String, String>,
+        name: &str,
+    ) -> ClassRef {
+        scope.get(name).map_or_else(
+            || ClassRef {
+                id: checker.class_id_for_name(name),
+                // Extract just the class name (remove module prefix if present)
+                name: name.split('.').next_back().unwrap_or(name).to_owned(),
+            },
+            |nested| ClassRef {
+                id: Some(checker.qualify(nested)),
+                name: nested.clone(),
+            },
+        )
+    }
+
+    /// Collect the classes named in method parameter and return annotations.
+    fn collect_dependency_types(checker: &Checker, class: &ast::StmtClassDef) -> IndexSet<String> {
+        let mut dependencies = IndexSet::new();
+        for stmt in &class.body {
+            let ast::Stmt::FunctionDef(function) = stmt else {
+                continue;
+            };
+            let annotations = function
+                .parameters
+                .iter()
+                .filter_map(|parameter| parameter.annotation())
+                .chain(function.returns.as_deref());
+            for annotation in annotations {
+                dependencies.extend(
+                    type_analyzer::extract_composition_types(annotation, checker)
+                        .into_iter()
+                        .map(|dependency| dependency.name),
+                );
+            }
+        }
+        dependencies
+    }
+
+    /// Collect attributes assigned through `self` in any method body, skipping names that are
+    /// already declared at class level. Annotated assignments also feed composition detection.
+    fn collect_class_instance_attributes(
+        checker: &Checker,
+        class: &ast::StmtClassDef,
+        members: &IndexSet<ClassMember>,
+        composition_types: &mut IndexSet<(String, CompositionType)>,
+    ) -> Vec<Attribute> {
+        let declared: HashSet<&str> = members.iter().map(ClassMember::name).collect();
+        // Slots come first, typed by the assignments found below
+        let mut instance_attributes: IndexMap<&str, Attribute> = slot_names(&class.body)
+            .into_iter()
+            .filter(|name| !declared.contains(name))
+            .map(|name| {
+                let attribute = Attribute::new(name, "Any", checker.visibility_for_name(name));
+                (name, attribute)
+            })
+            .collect();
+        for stmt in &class.body {
+            let ast::Stmt::FunctionDef(function) = stmt else {
+                continue;
+            };
+            for found in collect_instance_attributes(function, checker.semantic()) {
+                if declared.contains(found.name) {
+                    continue;
+                }
+
+                let annotation = Self::instance_attribute_annotation(function, &found);
+                if let Some(annotation) = annotation {
+                    composition_types.extend(
+                        type_analyzer::extract_composition_types(annotation, checker)
+                            .into_iter()
+                            .map(|comp_type| (found.name.to_owned(), comp_type)),
+                    );
+                }
+                let type_annotation = annotation
+                    .map(|annotation| checker.generator().expr(annotation))
+                    .or_else(|| {
+                        found
+                            .value
+                            .and_then(type_analyzer::infer_literal_type)
+                            .map(str::to_owned)
+                    });
+
+                match instance_attributes.entry(found.name) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(Attribute::new(
+                            found.name,
+                            type_annotation.unwrap_or_else(|| "Any".to_owned()),
+                            checker.visibility_for_name(found.name),
+                        ));
+                    }
+                    // A later assignment may carry the type an earlier one lacked
+                    Entry::Occupied(mut entry) => {
+                        if let Some(type_annotation) = type_annotation {
+                            if entry.get().type_annotation == "Any" {
+                                entry.get_mut().type_annotation = type_annotation;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        instance_attributes.into_values().collect()
+    }
+
+    /// Resolve the type of an instance attribute assignment: an explicit annotation wins,
+    /// then the annotation of the method parameter it was assigned from.
+    fn instance_attribute_annotation<'a>(
+        function: &'a ast::StmtFunctionDef,
+        attribute: &InstanceAttribute<'a>,
+    ) -> Option<&'a Expr> {
+        if attribute.annotation.is_some() {
+            return attribute.annotation;
+        }
+
+        let Some(Expr::Name(ast::ExprName { id, .. })) = attribute.value else {
+            return None;
+        };
+
+        let parameters = &function.parameters;
+        parameters
+            .posonlyargs
+            .iter()
+            .chain(&parameters.args)
+            .chain(&parameters.kwonlyargs)
+            .find(|parameter| parameter.parameter.name.as_str() == id.as_str())
+            .and_then(|parameter| parameter.parameter.annotation.as_deref())
     }
 
     /// Returns true if the function is a property setter or deleter (e.g. @name.setter, @name.deleter).
@@ -214,12 +1063,47 @@ impl ClassDiagram {
         })
     }
 
+    /// The members declared by a class body statement. An assignment records every name it
+    /// binds (`a = b = 0`, `x, y = 1, 2`), typed from the value paired with it.
+    fn process_stmt_to_members(checker: &Checker, stmt: &ast::Stmt) -> Vec<ClassMember> {
+        let mut bound = Vec::new();
+        match stmt {
+            ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                for target in targets {
+                    bind_names(target, Some(value), &mut bound);
+                }
+            }
+            ast::Stmt::AugAssign(ast::StmtAugAssign { target, value, .. }) => {
+                bind_names(target, Some(value), &mut bound);
+            }
+            _ => {
+                return Self::process_stmt_to_member(checker, stmt)
+                    .into_iter()
+                    .collect()
+            }
+        }
+
+        bound
+            .into_iter()
+            .map(|(name, value)| {
+                ClassMember::Attribute(Attribute::new(
+                    name,
+                    value
+                        .and_then(type_analyzer::infer_literal_type)
+                        .unwrap_or("Any"),
+                    checker.visibility_for_name(name),
+                ))
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_lines)]
     fn process_stmt_to_member(checker: &Checker, stmt: &ast::Stmt) -> Option<ClassMember> {
         match stmt {
             ast::Stmt::AnnAssign(ast::StmtAnnAssign {
                 target,
                 annotation,
+                value,
                 simple,
                 ..
             }) => {
@@ -232,69 +1116,36 @@ impl ClassDiagram {
                 };
 
                 let target_name = target.to_string();
-                let annotation_name = checker.generator().expr(annotation.as_ref());
-                let is_dunder = target_name.starts_with("__") && target_name.ends_with("__");
-                let is_private = target_name.starts_with('_') && !is_dunder;
+                // `ClassVar[int]` is shown as a static `int`; a bare `Final` takes the type of
+                // its value
+                let (declared, qualifiers) = type_analyzer::strip_qualifiers(annotation, checker);
+                let annotation_name = declared.map_or_else(
+                    || {
+                        value
+                            .as_deref()
+                            .and_then(type_analyzer::infer_literal_type)
+                            .unwrap_or("Any")
+                            .to_owned()
+                    },
+                    |declared| checker.generator().expr(declared),
+                );
+                let visibility = checker.visibility_for_name(&target_name);
 
                 Some(ClassMember::Attribute(Attribute {
-                    name: target_name,
-                    type_annotation: annotation_name,
-                    visibility: if is_private {
-                        Visibility::Private
-                    } else {
-                        Visibility::Public
-                    },
+                    is_static: qualifiers.class_var,
+                    is_final: qualifiers.is_final,
+                    is_init_var: qualifiers.init_var,
+                    ..Attribute::new(target_name, annotation_name, visibility)
                 }))
             }
 
-            ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
-                // Handle simple assignments (like enum members)
-                let value_type = match value.as_ref() {
-                    Expr::BoolOp(_) | Expr::BooleanLiteral(_) => "bool",
-                    Expr::BinOp(_) | Expr::UnaryOp(_) => "int",
-                    Expr::Lambda(_) => "Callable",
-                    Expr::DictComp(_) | Expr::Dict(_) => "dict",
-                    Expr::Set(_) | Expr::SetComp(_) => "set",
-                    Expr::FString(_) | Expr::StringLiteral(_) => "str",
-                    Expr::NoneLiteral(_) => "None",
-                    Expr::BytesLiteral(_) => "bytes",
-                    Expr::EllipsisLiteral(_) => "...",
-                    Expr::ListComp(_) | Expr::List(_) => "list",
-                    Expr::Tuple(_) => "tuple",
-                    Expr::NumberLiteral(inner) => match inner.value {
-                        Number::Int(_) => "int",
-                        Number::Float(_) => "float",
-                        Number::Complex { .. } => "complex",
-                    },
-                    _ => "",
-                };
-
-                // For now, just handle the first target (typical for enums and simple assignments)
-                if let Some(Expr::Name(ast::ExprName { id: target, .. })) = targets.first() {
-                    let target_name = target.to_string();
-                    let type_annotation = if value_type.is_empty() {
-                        "Any"
-                    } else {
-                        value_type
-                    }
-                    .to_owned();
-
-                    return Some(ClassMember::Attribute(Attribute {
-                        name: target_name,
-                        type_annotation,
-                        visibility: Visibility::Public, // Simple assignments are always public
-                    }));
-                }
-
-                None
-            }
-
             ast::Stmt::FunctionDef(ast::StmtFunctionDef {
                 name,
                 is_async,
                 parameters,
                 returns,
                 decorator_list,
+                body,
                 ..
             }) => {
                 // Skip property setters and deleters - they're implementation details of the property
@@ -302,9 +1153,13 @@ impl ClassDiagram {
                     return None;
                 }
 
-                let is_dunder = name.starts_with("__") && name.ends_with("__");
-                let is_private = name.starts_with('_') && !is_dunder;
+                let visibility = checker.visibility_for_name(name);
                 let is_static = is_staticmethod(decorator_list, checker.semantic());
+                // Types documented in the docstring fill in what the annotations leave out
+                let documented = docstring(body)
+                    .filter(|_| checker.docstring_types())
+                    .map(DocstringTypes::parse)
+                    .unwrap_or_default();
 
                 // @property getters: show as attributes (read-only) instead of methods
                 if is_property(
@@ -312,31 +1167,32 @@ impl ClassDiagram {
                     std::iter::empty::<QualifiedName>(),
                     checker.semantic(),
                 ) {
-                    let return_type = returns.as_ref().map_or_else(
-                        || {
-                            simple_magic_return_type(name)
-                                .map_or_else(|| "Any".to_owned(), String::from)
-                        },
-                        |target| checker.generator().expr(target.as_ref()),
-                    );
-                    return Some(ClassMember::Attribute(Attribute {
-                        name: name.to_string(),
-                        type_annotation: return_type,
-                        visibility: if is_private {
-                            Visibility::Private
-                        } else {
-                            Visibility::Public
-                        },
-                    }));
+                    let return_type = returns
+                        .as_ref()
+                        .map(|target| checker.generator().expr(target.as_ref()))
+                        .or_else(|| documented.returns())
+                        .or_else(|| simple_magic_return_type(name).map(String::from))
+                        .unwrap_or_else(|| "Any".to_owned());
+                    return Some(ClassMember::Attribute(Attribute::new(
+                        name.to_string(),
+                        return_type,
+                        visibility,
+                    )));
                 }
 
                 let mut param_gen = ParameterGenerator::new();
-                param_gen.unparse_parameters(parameters);
-                let params = param_gen.generate();
+                param_gen.unparse_parameters(parameters, checker);
+                let mut params = param_gen.generate();
+                for param in &mut params {
+                    if param.type_annotation.is_none() {
+                        param.type_annotation = documented.parameter(&param.name).map(String::from);
+                    }
+                }
 
                 let returns = returns
                     .as_ref()
                     .map(|target| checker.generator().expr(target.as_ref()))
+                    .or_else(|| documented.returns())
                     .or_else(|| simple_magic_return_type(name).map(String::from));
 
                 let mut decorators = vec![];
@@ -359,11 +1215,8 @@ impl ClassDiagram {
                     name: name.to_string(),
                     parameters: params,
                     return_type: returns,
-                    visibility: if is_private {
-                        Visibility::Private
-                    } else {
-                        Visibility::Public
-                    },
+                    inferred_return_type: None,
+                    visibility,
                     is_static,
                     is_abstract: is_abstract(decorator_list, checker.semantic()),
                     is_async: *is_async,
@@ -392,6 +1245,14 @@ impl ClassDiagram {
             return BaseKind::Skip;
         }
 
+        // Skip factory bases like `namedtuple("Point", "x y")`; their fields become attributes.
+        if base
+            .as_call_expr()
+            .is_some_and(|call| ClassFactory::from_call(call, checker.semantic()).is_some())
+        {
+            return BaseKind::Skip;
+        }
+
         let qualified_name = checker.semantic().resolve_qualified_name(base);
 
         if qualified_name.as_ref().is_some_and(|name| {
@@ -400,7 +1261,10 @@ impl ClassDiagram {
                 || is_abc_qualified_name(name)
                 || matches!(
                     name.segments(),
-                    ["typing" | "typing_extensions", "Protocol"]
+                    [
+                        "typing" | "typing_extensions",
+                        "Protocol" | "NamedTuple" | "TypedDict"
+                    ]
                 )
         }) {
             return BaseKind::Skip;
@@ -422,62 +1286,158 @@ impl ClassDiagram {
             .trim_matches('`')
             .to_string();
 
+        let base_id = checker.class_id_for_name(&base_display);
+
         // Check if the base class is abstract or a protocol (either built-in or user-defined).
-        let base_is_abstract_or_protocol = self.diagram.is_abstract_or_interface(&base_display)
+        let base_is_abstract_or_protocol = self
+            .diagram
+            .is_abstract_or_interface(base_id.as_deref().unwrap_or(&base_display))
             || detector.is_stdlib_abstract_or_protocol(base);
 
         BaseKind::InheritanceTarget {
             name: base_display,
+            id: base_id,
             is_abstract_or_protocol: base_is_abstract_or_protocol,
         }
     }
 
     /// Add source code to the diagram (for stdin/WASM - uses Python defaults)
     pub fn add_source(&mut self, source: &str) {
-        self.add_source_with_options(source, PySourceType::Python, ModuleKind::Module);
+        self.add_source_with_options(
+            source,
+            Path::new(""),
+            &[],
+            PySourceType::Python,
+            ModuleKind::Module,
+        );
     }
 
     /// Add source code from a file path (infers source type and module kind)
     pub fn add_file(&mut self, source: &str, path: &Path) {
+        self.add_file_with_module(source, path, &[]);
+    }
+
+    /// Add source code from a file path whose dotted module path within the project is known
+    /// (e.g. `["pkg", "models"]` for `pkg/models.py`). Classes are identified by their
+    /// fully qualified name and relative imports are resolved against the module path.
+    pub fn add_file_with_module(&mut self, source: &str, path: &Path, module_path: &[String]) {
         let source_type = PySourceType::from(path);
         let module_kind = Self::module_kind_for_path(path);
-        self.add_source_with_options(source, source_type, module_kind);
+        self.add_source_with_options(source, path, module_path, source_type, module_kind);
     }
 
     fn add_source_with_options(
         &mut self,
         source: &str,
+        path: &Path,
+        module_path: &[String],
         source_type: PySourceType,
         module_kind: ModuleKind,
     ) {
-        let source_kind = SourceKind::Python {
-            code: source.to_owned(),
-            is_stub: false,
+        // Notebooks are JSON; their code cells are concatenated into one Python source
+        let source_kind = match SourceKind::from_source_code(source.to_owned(), source_type) {
+            Ok(Some(source_kind)) => source_kind,
+            Ok(None) => {
+                debug!("Skipping non-Python notebook: {}", path.display());
+                return;
+            }
+            Err(err) => {
+                warn!("Failed to read {}: {err}", path.display());
+                return;
+            }
         };
 
         let parsed = Self::parse_python(source_kind.source_code(), source_type);
+        Self::report_syntax_errors(&parsed, &source_kind, path);
         let mut checker = Self::build_checker(
             &parsed.stylist,
             &parsed.locator,
             &parsed.python_ast,
+            path,
             module_kind,
         );
+        checker.set_module_path(module_path, matches!(module_kind, ModuleKind::Package));
+        checker.set_stub(source_type.is_stub());
+        checker.set_visibility_policy(self.options.visibility_policy);
+        checker.set_docstring_types(self.options.docstring_types);
         checker.see_imports(&parsed.python_ast);
+        checker.see_classes(&parsed.python_ast);
 
         self.add_classes_from_ast(&checker, &parsed.python_ast);
     }
 
     fn add_classes_from_ast(&mut self, checker: &Checker, python_ast: &[ast::Stmt]) {
-        for stmt in python_ast {
-            if let ast::Stmt::ClassDef(class) = stmt {
+        self.add_classes_from_body(checker, python_ast, false);
+    }
+
+    /// Add the classes defined in a module-level body, descending into compound statements
+    /// such as `if TYPE_CHECKING:` or `try: ... except ImportError:`. Classes found there are
+    /// marked conditional so that definitions from different branches are merged.
+    fn add_classes_from_body(
+        &mut self,
+        checker: &Checker,
+        body: &[ast::Stmt],
+        is_conditional: bool,
+    ) {
+        for stmt in body {
+            match stmt {
                 // we only care about class definitions
-                self.add_class(checker, class, 1);
+                ast::Stmt::ClassDef(class) => {
+                    self.add_class_in_scope(checker, class, None, &HashMap::new(), is_conditional);
+                }
+                // Functional declarations, e.g. `Color = Enum("Color", "RED GREEN")`
+                ast::Stmt::Assign(ast::StmtAssign { targets, value, .. }) => {
+                    if let ([Expr::Name(target)], Expr::Call(call)) =
+                        (targets.as_slice(), value.as_ref())
+                    {
+                        self.add_functional_class(
+                            checker,
+                            target.id.as_str(),
+                            call,
+                            is_conditional,
+                        );
+                    }
+                }
+                ast::Stmt::If(ast::StmtIf {
+                    body,
+                    elif_else_clauses,
+                    ..
+                }) => {
+                    self.add_classes_from_body(checker, body, true);
+                    for clause in elif_else_clauses {
+                        self.add_classes_from_body(checker, &clause.body, true);
+                    }
+                }
+                ast::Stmt::Try(ast::StmtTry {
+                    body,
+                    handlers,
+                    orelse,
+                    finalbody,
+                    ..
+                }) => {
+                    self.add_classes_from_body(checker, body, true);
+                    for handler in handlers {
+                        let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                        self.add_classes_from_body(checker, &handler.body, true);
+                    }
+                    self.add_classes_from_body(checker, orelse, true);
+                    self.add_classes_from_body(checker, finalbody, true);
+                }
+                ast::Stmt::With(ast::StmtWith { body, .. }) => {
+                    self.add_classes_from_body(checker, body, true);
+                }
+                ast::Stmt::Match(ast::StmtMatch { cases, .. }) => {
+                    for case in cases {
+                        self.add_classes_from_body(checker, &case.body, true);
+                    }
+                }
+                _ => {}
             }
         }
     }
 
     fn module_kind_for_path(path: &Path) -> ModuleKind {
-        if path.ends_with("__init__.py") {
+        if path.file_stem().is_some_and(|stem| stem == "__init__") {
             ModuleKind::Package
         } else {
             ModuleKind::Module
@@ -487,12 +1447,37 @@ impl ClassDiagram {
     fn parse_python(source: &str, source_type: PySourceType) -> ParsedPython<'_> {
         let parsed = parse_unchecked_source(source, source_type);
         let stylist = Stylist::from_tokens(parsed.tokens(), source);
+        let syntax_errors = parsed.errors().to_vec();
         let python_ast = parsed.into_suite().to_vec();
 
         ParsedPython {
             python_ast,
             locator: Locator::new(source),
             stylist,
+            syntax_errors,
+        }
+    }
+
+    /// Log the syntax errors found while parsing, pointing notebook errors back to their cell.
+    fn report_syntax_errors(parsed: &ParsedPython, source_kind: &SourceKind, path: &Path) {
+        if parsed.syntax_errors.is_empty() {
+            return;
+        }
+
+        let line_index = LineIndex::from_source_text(parsed.locator.contents());
+        for error in &parsed.syntax_errors {
+            let row = line_index.line_index(error.location.start());
+            let cell_location = if let SourceKind::IpyNotebook(notebook) = source_kind {
+                let index = notebook.index();
+                index.cell(row).zip(index.cell_row(row))
+            } else {
+                None
+            };
+            let location = cell_location.map_or_else(
+                || format!("line {row}"),
+                |(cell, cell_row)| format!("cell {cell}, line {cell_row}"),
+            );
+            warn!("{}: {location}: {}", path.display(), error.error);
         }
     }
 
@@ -500,19 +1485,16 @@ impl ClassDiagram {
         stylist: &'a Stylist<'a>,
         locator: &'a Locator<'a>,
         python_ast: &'a [ast::Stmt],
+        path: &'a Path,
         module_kind: ModuleKind,
     ) -> Checker<'a> {
-        // Use a static dummy path for the semantic model (it's only used for diagnostics)
-        static DUMMY_PATH: &str = "";
-        let dummy = Path::new(DUMMY_PATH);
-
         let module = Module {
             kind: module_kind,
-            source: ModuleSource::File(dummy),
+            source: ModuleSource::File(path),
             python_ast,
             name: None,
         };
-        let semantic = SemanticModel::new(&[], dummy, module);
+        let semantic = SemanticModel::new(&[], path, module);
         Checker::new(stylist, locator, semantic)
     }
 }
@@ -521,6 +1503,7 @@ struct ParsedPython<'a> {
     python_ast: Vec<ast::Stmt>,
     locator: Locator<'a>,
     stylist: Stylist<'a>,
+    syntax_errors: Vec<ParseError>,
 }
 
 #[cfg(test)]
@@ -15,6 +15,8 @@ class TestClass:
 
     let expected_output = r"classDiagram
     class TestClass {
+        + int x
+        + int y
         + \_\_init__(self, x, y) None
         + add(self, x, y) int
         + subtract(self, x, y) int
@@ -218,7 +220,7 @@ class Car:
         pass
 ";
 
-    let expected_output = "classDiagram
+    let expected_output = r#"classDiagram
     class Engine {
         + int horsepower
     }
@@ -233,10 +235,10 @@ class Car:
         + drive(self) None
     }
 
-    Car *-- Engine
+    Car *-- "1" Engine : engine
 
-    Car *-- Wheel
-";
+    Car o-- "*" Wheel : wheels
+"#;
 
     test_diagram(source, expected_output);
 }
@@ -254,7 +256,7 @@ class Car:
     part: Engine | Wheel
 ";
 
-    let expected_output = "classDiagram
+    let expected_output = r#"classDiagram
     class Engine {
         + int horsepower
     }
@@ -267,11 +269,56 @@ class Car:
         + Engine | Wheel part
     }
 
-    Car *-- Engine
+    Car *-- "0..1" Engine : part
+
+    Car *-- "0..1" Wheel : part
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_composition_kinds_and_cardinality() {
+    let source = "
+from typing import Optional
 
-    Car *-- Wheel
+class Engine: ...
+class Driver: ...
+class Wheel: ...
+class Part: ...
+
+class Car:
+    engine: Engine
+    driver: Optional[Driver]
+    wheels: set[Wheel]
+    parts: dict[str, Part]
 ";
 
+    let expected_output = r#"classDiagram
+    class Driver
+
+    class Engine
+
+    class Part
+
+    class Wheel
+
+    class Car {
+        + Engine engine
+        + Optional[Driver] driver
+        + set[Wheel] wheels
+        + dict[str, Part] parts
+    }
+
+    Car *-- "1" Engine : engine
+
+    Car o-- "0..1" Driver : driver
+
+    Car o-- "*" Wheel : wheels
+
+    Car --> "*" Part : parts [str]
+"#;
+
     test_diagram(source, expected_output);
 }
 
@@ -315,30 +362,45 @@ class User(UserBase):
         orm_mode = True
 ";
 
-    let expected_output = "classDiagram
+    let expected_output = r#"classDiagram
     class ItemBase {
+        <<pydantic>>
         + str title
-        + str | None description
+        + str | None description = None
     }
 
     class Item {
+        <<pydantic>>
         + int id
         + int owner_id
     }
 
-    class ItemCreate
+    class Item_Config["Item.Config"] {
+        + bool orm_mode
+    }
+
+    class ItemCreate {
+        <<pydantic>>
+    }
 
     class UserBase {
+        <<pydantic>>
         + str email
     }
 
     class User {
+        <<pydantic>>
         + int id
         + bool is_active
-        + list[Item] items
+        + list[Item] items = []
+    }
+
+    class User_Config["User.Config"] {
+        + bool orm_mode
     }
 
     class UserCreate {
+        <<pydantic>>
         + str password
     }
 
@@ -348,13 +410,165 @@ class User(UserBase):
 
     Item --|> ItemBase
 
+    Item_Config -- Item : nested
+
     UserBase --|> pydantic.BaseModel
 
     UserCreate --|> UserBase
 
     User --|> UserBase
 
-    User *-- Item
+    User_Config -- User : nested
+
+    User o-- "*" Item : items
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_nested_classes() {
+    let source = "
+class Base: ...
+
+class Outer:
+    class Meta:
+        ordering: int
+
+    class Builder(Base):
+        size: int
+
+    class Special(Meta): ...
+
+    meta: Meta
+";
+
+    let expected_output = r#"classDiagram
+    class Base
+
+    class Outer_Meta["Outer.Meta"] {
+        + int ordering
+    }
+
+    class Outer {
+        + Meta meta
+    }
+
+    class Outer_Builder["Outer.Builder"] {
+        + int size
+    }
+
+    class Outer_Special["Outer.Special"]
+
+    Outer_Meta -- Outer : nested
+
+    Outer_Builder --|> Base
+
+    Outer_Builder -- Outer : nested
+
+    Outer_Special --|> Outer_Meta
+
+    Outer_Special -- Outer : nested
+
+    Outer *-- "1" Outer_Meta : meta
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_nested_class_references_sibling() {
+    let source = "
+class Outer:
+    class Meta:
+        ordering: int
+
+    class Builder:
+        meta: Meta
+";
+
+    let expected_output = r#"classDiagram
+    class Outer
+
+    class Outer_Meta["Outer.Meta"] {
+        + int ordering
+    }
+
+    class Outer_Builder["Outer.Builder"] {
+        + Meta meta
+    }
+
+    Outer_Meta -- Outer : nested
+
+    Outer_Builder -- Outer : nested
+
+    Outer_Builder *-- "1" Outer_Meta : meta
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_conditional_classes() {
+    let source = "
+import sys
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from collections.abc import Sequence
+
+    class Hidden:
+        items: Sequence[int]
+
+try:
+    from fast import Parser
+except ImportError:
+    class Parser:
+        def parse(self) -> None: ...
+
+if sys.version_info >= (3, 11):
+    class Compat:
+        def new_api(self) -> None: ...
+else:
+    class Compat:
+        def old_api(self) -> None: ...
+";
+
+    let expected_output = "classDiagram
+    class Compat {
+        + new_api(self) None
+        + old_api(self) None
+    }
+
+    class Hidden {
+        + Sequence[int] items
+    }
+
+    class Parser {
+        + parse(self) None
+    }
+";
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_conditional_classes_keep_first_signature() {
+    let source = "
+try:
+    class Parser:
+        def parse(self, text: str) -> None: ...
+except ImportError:
+    class Parser:
+        def parse(self, text: str, strict: bool = False) -> None: ...
+        def reset(self) -> None: ...
+";
+
+    let expected_output = "classDiagram
+    class Parser {
+        + parse(self, text) None
+        + reset(self) None
+    }
 ";
 
     test_diagram(source, expected_output);
@@ -377,13 +591,90 @@ class Thing:
     let expected_output = r"classDiagram
     class Thing {
         + @overload \_\_init__(self, x, y) None
-        + \_\_init__(self, x, y) None
     }
 ";
 
     test_diagram(source, expected_output);
 }
 
+#[test]
+fn test_overload_styles() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::{OverloadStyle, ParameterStyle};
+
+    let source = "
+from typing import overload
+class Cache:
+    @overload
+    def get(self, key: int, *, strict: bool = False) -> bytes: ...
+    @overload
+    def get(self, key: str, *, strict: bool = False) -> bytes | None: ...
+    def get(self, key, *, strict=False):
+        return None
+
+    def clear(self) -> None: ...
+";
+    let render = |overload_style| {
+        let mut diagram = ClassDiagram::new(RenderOptions {
+            parameter_style: ParameterStyle::Types,
+            overload_style,
+            ..RenderOptions::default()
+        });
+        diagram.add_source(source);
+        diagram.render().unwrap_or_default()
+    };
+
+    let collapsed = "classDiagram
+    class Cache {
+        + @overload get(self, key: int | str, *, strict: bool) bytes | None
+        + clear(self) None
+    }
+";
+    assert_eq!(render(OverloadStyle::Collapse).trim(), collapsed.trim());
+
+    let expanded = "classDiagram
+    class Cache {
+        + @overload get(self, key: int, *, strict: bool = False) bytes
+        + @overload get(self, key: str, *, strict: bool = False) bytes | None
+        + clear(self) None
+    }
+";
+    assert_eq!(render(OverloadStyle::Expand).trim(), expanded.trim());
+}
+
+#[test]
+fn test_overloads_of_different_arity() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::ParameterStyle;
+
+    let source = "
+from typing import overload
+class Cache:
+    @overload
+    def get(self, key: str) -> bytes | None: ...
+    @overload
+    def get(self, key: str, default: bytes) -> bytes: ...
+    def get(self, key, default=None):
+        return default
+";
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        parameter_style: ParameterStyle::Types,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+
+    let expected_output = "classDiagram
+    class Cache {
+        + @overload get(self, key: str) bytes | None
+        + @overload get(self, key: str, default: bytes) bytes
+    }
+";
+    assert_eq!(
+        diagram.render().unwrap_or_default().trim(),
+        expected_output.trim()
+    );
+}
+
 #[test]
 fn test_class_diagram_object_base() {
     let source = "
@@ -465,9 +756,9 @@ class Color(Enum):
     let expected_output = "classDiagram
     class Color {
         <<enumeration>>
-        + int RED
-        + int GREEN
-        + int BLUE
+        + RED
+        + GREEN
+        + BLUE
     }
 ";
 
@@ -475,58 +766,116 @@ class Color(Enum):
 }
 
 #[test]
-fn test_staticmethod() {
-    let source = "
-class Thing:
-    @staticmethod
-    def static_method(x: int, y: int) -> int:
-        return x + y
-";
-    let expected_output = "classDiagram
-    class Thing {
-        + @staticmethod static_method(x, y) int$
-    }
-";
+fn test_enum_members_and_kinds() {
+    use crate::render::mermaid_renderer::RenderOptions;
 
-    test_diagram(source, expected_output);
-}
+    let source = r#"
+from enum import Enum, Flag, IntEnum, StrEnum, auto, nonmember
+
+class Color(Enum):
+    _ignore_ = ["Shade"]
+    Shade = 0
+    RED = 1
+    GREEN = auto()
+    __secret = 2
+    limit = nonmember(3)
+    describe = lambda self: self.name
 
-#[test]
-fn test_property_as_attribute() {
-    let source = "
-class Person:
     @property
-    def name(self) -> str:
-        return self._name
+    def hex(self) -> str: ...
 
-    @name.setter
-    def name(self, value: str) -> None:
-        self._name = value
+    def lighter(self) -> "Color": ...
 
-    @name.deleter
-    def name(self) -> None:
-        del self._name
-";
-    let expected_output = "classDiagram
-    class Person {
-        + str name
+class Priority(IntEnum):
+    LOW = 1
+    HIGH = 2
+
+class Mode(StrEnum):
+    READ = "r"
+
+class Permission(Flag):
+    EXECUTE = auto()
+
+Size = IntEnum("Size", [("SMALL", 1), ("LARGE", 2)])
+"#;
+
+    let expected_output = r#"classDiagram
+    class Color {
+        <<enumeration>>
+        + RED = 1
+        + GREEN = auto()
+        - int \_\_secret
+        + Any limit
+        + Callable describe
+        + str hex
+        + lighter(self) "Color"
     }
-";
 
-    test_diagram(source, expected_output);
+    class Mode {
+        <<strenum>>
+        + READ = "r"
+    }
+
+    class Permission {
+        <<flag>>
+        + EXECUTE = auto()
+    }
+
+    class Priority {
+        <<intenum>>
+        + LOW = 1
+        + HIGH = 2
+    }
+
+    class Size {
+        <<intenum>>
+        + SMALL = 1
+        + LARGE = 2
+    }
+"#;
+
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        show_enum_values: true,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected_output.trim());
 }
 
 #[test]
-fn test_property_no_return_annotation() {
-    let source = "
-class Thing:
-    @property
-    def value(self):
-        return 42
-";
-    let expected_output = "classDiagram
-    class Thing {
-        + Any value
+fn test_multiple_target_and_unpacking_assignments() {
+    let source = r#"
+class Settings:
+    retries = timeout = 3
+    host, port = "localhost", 8080
+    (low, high), limits = (0, 1.5), [2, 3]
+    first, *rest, last = "a", 1, 2, b"z"
+    count: int = 0
+    count += 1
+    total += 1.0
+    _a = _b = 0
+    _x, _y = 1, "y"
+"#;
+
+    let expected_output = r"classDiagram
+    class Settings {
+        + int retries
+        + int timeout
+        + str host
+        + int port
+        + int low
+        + float high
+        + list limits
+        + str first
+        + Any rest
+        + bytes last
+        + int count
+        + float total
+        - int \_a
+        - int \_b
+        - int \_x
+        - str \_y
     }
 ";
 
@@ -534,52 +883,410 @@ class Thing:
 }
 
 #[test]
-fn test_concrete_generic_base() {
+fn test_slots_declare_instance_attributes() {
     let source = r#"
-from typing import TypeVar, Generic
-IndexType = TypeVar("IndexType")
-
-class Store(Generic[IndexType]):
-    def insert(self, data) -> None:
-        pass
+class Point:
+    __slots__ = ("x", "y", "_cache", "__weakref__")
 
-class MemoryStore(Store[int]):
-    def insert(self, data) -> None:
-        self.storage.append(data)
+    def __init__(self, x: float, y: float) -> None:
+        self.x = x
+        self.y = y
+        self.label = "origin"
 "#;
 
-    let expected_output = r#"classDiagram
-    class Store ~IndexType~ {
-        + insert(self, data) None
-    }
-
-    class MemoryStore {
-        + insert(self, data) None
+    let expected_output = r"classDiagram
+    class Point {
+        + float x
+        + float y
+        - Any \_cache
+        + str label
+        + \_\_init__(self, x, y) None
     }
-
-    MemoryStore --|> Store"#;
+";
 
     test_diagram(source, expected_output);
 }
 
 #[test]
-fn test_abstract_generic_inheritance() {
+fn test_infer_return_types() {
+    use crate::render::mermaid_renderer::RenderOptions;
+
     let source = r#"
-from typing import TypeVar, Generic
-from abc import ABC, abstractmethod
-IndexType = TypeVar("IndexType")
+from decimal import Decimal
 
-class Store(ABC, Generic[IndexType]):
-    @abstractmethod
-    def insert(self, data) -> None:
-        pass
+class Widget:
+    def reset(self):
+        self.count = 0
 
-class MemoryStore(Store[int]):
-    def insert(self, data) -> None:
-        self.storage.append(data)
-"#;
+    def label(self, short):
+        if short:
+            return "w"
+        return "widget"
 
-    let expected_output = r#"classDiagram
+    def find(self, key):
+        if key:
+            return 1
+
+    def chain(self):
+        return self
+
+    @classmethod
+    def create(cls):
+        return cls()
+
+    def copy(self):
+        return Widget()
+
+    def items(self):
+        yield 1
+        yield "a"
+
+    async def fetch(self):
+        return b""
+
+    def compute(self, x):
+        return x + 1
+
+    def fail(self):
+        return ValueError("bad")
+
+    def price(self):
+        return Decimal(1)
+
+    def same(this):
+        return this
+
+    @classmethod
+    def kind(cls):
+        return cls
+
+    @staticmethod
+    def make(self):
+        return self
+
+    def size(self) -> int:
+        return 0
+
+    def abstract(self):
+        raise NotImplementedError
+"#;
+
+    let expected_output = r#"classDiagram
+    class Widget {
+        + int count
+        + reset(self) None?
+        + label(self, short) str?
+        + find(self, key) int | None?
+        + chain(self) Widget?
+        + @classmethod create(cls) Widget?
+        + copy(self) Widget?
+        + items(self) Iterator[int | str]?
+        + async fetch(self) Awaitable[bytes]?
+        + compute(self, x)
+        + fail(self)
+        + price(self)
+        + same(this) Widget?
+        + @classmethod kind(cls) type[Widget]?
+        + @staticmethod make(self)$
+        + size(self) int
+        + abstract(self)
+    }
+"#;
+
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        infer_return_types: true,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected_output.trim());
+}
+
+#[test]
+fn test_docstring_types() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::ParameterStyle;
+
+    let source = r#"
+class Point:
+    def move(self, dx, dy=0, label: str = ""):
+        """Move the point.
+
+        Args:
+            dx (int): Horizontal offset.
+            dy (float, optional): Vertical offset.
+            label (bytes): The annotation wins.
+
+        Returns:
+            Point: The moved point.
+        """
+
+    def scale(self, factor):
+        """Scale the point.
+
+        Parameters
+        ----------
+        factor : float or None
+            Scale factor.
+
+        Returns
+        -------
+        bool
+            Whether the point moved.
+        """
+
+    def read(self, path) -> bytes:
+        """Read the point from a file.
+
+        :type path: str
+        :rtype: str
+        """
+
+    @property
+    def norm(self):
+        """Length of the vector.
+
+        Returns:
+            float: The length.
+        """
+
+    def undocumented(self, value):
+        pass
+"#;
+
+    let expected_output = r#"classDiagram
+    class Point {
+        + float norm
+        + move(self, dx: int, dy: float, label: str) Point
+        + scale(self, factor: float | None) bool
+        + read(self, path: str) bytes
+        + undocumented(self, value)
+    }
+"#;
+
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        parameter_style: ParameterStyle::Types,
+        docstring_types: true,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected_output.trim());
+
+    // Without the option, docstrings are ignored
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        parameter_style: ParameterStyle::Types,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert!(output.contains("+ move(self, dx, dy, label: str)\n"));
+    assert!(output.contains("+ Any norm\n"));
+}
+
+#[test]
+fn test_docstring_notes() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::DocstringNotes;
+
+    let source = r#"
+class Base:
+    """Common base of the parsers,
+    shared by every format.
+
+    Details that stay out of the note.
+    """
+
+class Parser(Base):
+    """Parse <config> files with "quoted" # values."""
+
+    def parse(self): ...
+
+class Plain(Base):
+    pass
+"#;
+    let render = |options| {
+        let mut diagram = ClassDiagram::new(options);
+        diagram.add_source(source);
+        diagram.render().unwrap_or_default()
+    };
+
+    let expected_output = r#"classDiagram
+    class Base
+
+    class Parser {
+        + parse(self)
+    }
+
+    class Plain
+
+    note for Base "Common base of the parsers, shared by\nevery format."
+    note for Parser "Parse #lt;config#gt; files with #quot;quoted#quot; #35;\nvalues."
+
+    Parser --|> Base
+
+    Plain --|> Base
+"#;
+    let output = render(RenderOptions {
+        docstrings: DocstringNotes::FirstParagraph,
+        ..RenderOptions::default()
+    });
+    assert_eq!(output.trim(), expected_output.trim());
+
+    let output = render(RenderOptions {
+        docstrings: DocstringNotes::FirstLine,
+        docstring_max_length: 20,
+        docstring_line_length: 0,
+        ..RenderOptions::default()
+    });
+    assert!(output.contains("    note for Base \"Common base of...\"\n"));
+
+    // Notes are off by default
+    assert!(!render(RenderOptions::default()).contains("note for"));
+}
+
+#[test]
+fn test_unannotated_assignment_visibility() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::Visibility;
+
+    let source = "
+class Counter:
+    total = 0
+    _x = 0
+    _cache: dict = {}
+";
+    let render = |min_visibility| {
+        let mut diagram = ClassDiagram::new(RenderOptions {
+            min_visibility,
+            ..RenderOptions::default()
+        });
+        diagram.add_source(source);
+        diagram.render().unwrap_or_default()
+    };
+
+    let expected_output = r"classDiagram
+    class Counter {
+        + int total
+        - int \_x
+        - dict \_cache
+    }
+";
+    assert_eq!(render(Visibility::Private).trim(), expected_output.trim());
+
+    let public = render(Visibility::Public);
+    assert!(public.contains("+ int total"), "got: {public}");
+    assert!(!public.contains("_x"), "got: {public}");
+    assert!(!public.contains("_cache"), "got: {public}");
+}
+
+#[test]
+fn test_staticmethod() {
+    let source = "
+class Thing:
+    @staticmethod
+    def static_method(x: int, y: int) -> int:
+        return x + y
+";
+    let expected_output = "classDiagram
+    class Thing {
+        + @staticmethod static_method(x, y) int$
+    }
+";
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_property_as_attribute() {
+    let source = "
+class Person:
+    @property
+    def name(self) -> str:
+        return self._name
+
+    @name.setter
+    def name(self, value: str) -> None:
+        self._name = value
+
+    @name.deleter
+    def name(self) -> None:
+        del self._name
+";
+    let expected_output = "classDiagram
+    class Person {
+        + str name
+        - str _name
+    }
+";
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_property_no_return_annotation() {
+    let source = "
+class Thing:
+    @property
+    def value(self):
+        return 42
+";
+    let expected_output = "classDiagram
+    class Thing {
+        + Any value
+    }
+";
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_concrete_generic_base() {
+    let source = r#"
+from typing import TypeVar, Generic
+IndexType = TypeVar("IndexType")
+
+class Store(Generic[IndexType]):
+    def insert(self, data) -> None:
+        pass
+
+class MemoryStore(Store[int]):
+    def insert(self, data) -> None:
+        self.storage.append(data)
+"#;
+
+    let expected_output = r#"classDiagram
+    class Store ~IndexType~ {
+        + insert(self, data) None
+    }
+
+    class MemoryStore {
+        + insert(self, data) None
+    }
+
+    MemoryStore --|> Store"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_abstract_generic_inheritance() {
+    let source = r#"
+from typing import TypeVar, Generic
+from abc import ABC, abstractmethod
+IndexType = TypeVar("IndexType")
+
+class Store(ABC, Generic[IndexType]):
+    @abstractmethod
+    def insert(self, data) -> None:
+        pass
+
+class MemoryStore(Store[int]):
+    def insert(self, data) -> None:
+        self.storage.append(data)
+"#;
+
+    let expected_output = r#"classDiagram
     class Store ~IndexType~ {
         <<abstract>>
         + insert(self, data) None*
@@ -628,6 +1335,7 @@ class FancyStore(Store[datetime], Generic[FancyStorage]):
     }
 
     class FancyStore ~FancyStorage~ {
+        + FancyStorage storage
         + \_\_init__(self, fancy_store) None
         + insert(self, data) None
     }
@@ -644,28 +1352,130 @@ class FancyStore(Store[datetime], Generic[FancyStorage]):
 }
 
 #[test]
-fn test_non_default_direction_emitted() {
+fn test_instance_attributes() {
+    let source = "
+class Engine: ...
+
+class Car:
+    wheels: int
+
+    def __init__(self, engine: Engine, name) -> None:
+        self.engine = engine
+        self.name = name
+        self.wheels = 4
+        self.speed: float = 0
+        self._started = False
+        if name:
+            self.label = f'car {name}'
+
+    def start(self) -> None:
+        self.name: str = 'started'
+        self.engine = None
+        self._started = True
+
+    @staticmethod
+    def build(self) -> None:
+        self.ignored = 1
+";
+
+    let expected_output = r#"classDiagram
+    class Engine
+
+    class Car {
+        + int wheels
+        + Engine engine
+        + str name
+        + float speed
+        - bool \_started
+        + str label
+        + \_\_init__(self, engine, name) None
+        + start(self) None
+        + @staticmethod build(self) None$
+    }
+
+    Car *-- "1" Engine : engine
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_instance_attributes_tuple_unpacking() {
+    let source = "
+class Point:
+    def __init__(self, x: int, y: int) -> None:
+        self.x, self.y = x, y
+";
+
+    let expected_output = r"classDiagram
+    class Point {
+        + int x
+        + int y
+        + \_\_init__(self, x, y) None
+    }
+";
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_parameter_style_full() {
     use crate::render::mermaid_renderer::RenderOptions;
-    use crate::render::renderer::DiagramDirection;
+    use crate::render::renderer::ParameterStyle;
 
-    let source = "class Thing: ...";
-    let expected = "classDiagram
-    direction LR
+    let source = "
+class Point:
+    def move(self, dx: int, dy: int = 0, *, scale: float = 1.0) -> None: ...
 
-    class Thing
+    @classmethod
+    def origin(cls, /, label=None, **extra: str) -> None: ...
+
+    @staticmethod
+    def distance(a: Point, b: Point) -> float: ...
+";
+
+    let expected_output = "classDiagram
+    class Point {
+        + move(dx: int, dy: int = 0, *, scale: float = 1.0) None
+        + @classmethod origin(label=None, **extra: str) None
+        + @staticmethod distance(a: Point, b: Point) float$
+    }
 ";
+
     let options = RenderOptions {
-        direction: DiagramDirection::LR,
-        hide_private_members: false,
+        parameter_style: ParameterStyle::Full,
+        hide_self: true,
+        ..RenderOptions::default()
     };
     let mut diagram = ClassDiagram::new(options);
     diagram.add_source(source);
     let output = diagram.render().unwrap_or_default();
-    assert_eq!(output.trim(), expected.trim());
+    assert_eq!(output.trim(), expected_output.trim());
 }
 
 #[test]
-fn test_hide_private_members() {
+fn test_non_default_direction_emitted() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::DiagramDirection;
+
+    let source = "class Thing: ...";
+    let expected = "classDiagram
+    direction LR
+
+    class Thing
+";
+    let options = RenderOptions {
+        direction: DiagramDirection::LR,
+        ..RenderOptions::default()
+    };
+    let mut diagram = ClassDiagram::new(options);
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected.trim());
+}
+
+#[test]
+fn test_hide_private_members() {
     use crate::render::mermaid_renderer::RenderOptions;
 
     let source = "
@@ -707,6 +1517,709 @@ class Foo:
     );
 }
 
+#[test]
+fn test_visibility_policy_and_minimum_visibility() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::{Visibility, VisibilityPolicy};
+
+    let source = "
+class Account:
+    owner: str
+    _balance: int
+    __pin: int
+    def __init__(self) -> None: ...
+    def _audit(self) -> None: ...
+    def __verify(self) -> None: ...
+";
+    let render = |min_visibility| {
+        let mut diagram = ClassDiagram::new(RenderOptions {
+            min_visibility,
+            visibility_policy: VisibilityPolicy::Mangling,
+            ..RenderOptions::default()
+        });
+        diagram.add_source(source);
+        diagram.render().unwrap_or_default()
+    };
+
+    let expected = r"classDiagram
+    class Account {
+        + str owner
+        # int \_balance
+        - int \_\_pin
+        + \_\_init__(self) None
+        # \_audit(self) None
+        - \_\_verify(self) None
+    }
+";
+    assert_eq!(render(Visibility::Private).trim(), expected.trim());
+
+    let protected = render(Visibility::Protected);
+    assert!(protected.contains(r"# int \_balance"), "got: {protected}");
+    assert!(!protected.contains("__pin"), "got: {protected}");
+    assert!(!protected.contains("__verify"), "got: {protected}");
+
+    let public = render(Visibility::Public);
+    assert!(!public.contains("_balance"), "got: {public}");
+    assert!(!public.contains("_audit"), "got: {public}");
+}
+
+#[test]
+fn test_parallel_edges_labelled_and_merged() {
+    use crate::render::mermaid_renderer::RenderOptions;
+
+    let source = "
+class Address: ...
+
+class Order:
+    primary: Address
+    billing: Address
+";
+    let render = |merge_parallel_edges: bool| {
+        let mut diagram = ClassDiagram::new(RenderOptions {
+            merge_parallel_edges,
+            ..RenderOptions::default()
+        });
+        diagram.add_source(source);
+        diagram.render().unwrap_or_default()
+    };
+
+    let separate = render(false);
+    assert!(separate.contains("Order *-- \"1\" Address : primary\n"));
+    assert!(separate.contains("Order *-- \"1\" Address : billing\n"));
+
+    let merged = render(true);
+    assert!(merged.contains("Order *-- \"1\" Address : primary, billing\n"));
+    assert!(!merged.contains("Address : primary\n"));
+}
+
+#[test]
+fn test_dependencies_from_method_signatures() {
+    use crate::render::mermaid_renderer::RenderOptions;
+
+    let source = "
+class Engine: ...
+class Fuel: ...
+class Report: ...
+
+class Car:
+    engine: Engine
+
+    def refuel(self, fuel: Fuel, engine: Engine) -> None: ...
+    def report(self) -> list[Report]: ...
+    def copy(self) -> Car: ...
+";
+
+    let expected_output = r#"classDiagram
+    class Engine
+
+    class Fuel
+
+    class Report
+
+    class Car {
+        + Engine engine
+        + refuel(self, fuel, engine) None
+        + report(self) list[Report]
+        + copy(self) Car
+    }
+
+    Car ..> Fuel
+
+    Car ..> Report
+
+    Car *-- "1" Engine : engine
+"#;
+
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        show_dependencies: true,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected_output.trim());
+
+    // Off by default
+    let mut diagram = ClassDiagram::default();
+    diagram.add_source(source);
+    assert!(!diagram.render().unwrap_or_default().contains("..>"));
+}
+
+#[test]
+fn test_named_tuple_and_typed_dict_classes() {
+    let source = r#"
+from collections import namedtuple
+from typing import NamedTuple, NotRequired, Required, TypedDict
+
+class Person:
+    name: str
+
+class Point(NamedTuple):
+    x: int
+    y: int = 0
+
+class Range(namedtuple("Range", ["start", "stop"])):
+    def size(self) -> int: ...
+
+class Movie(TypedDict, total=False):
+    title: Required[str]
+    year: int
+    director: NotRequired[Person]
+"#;
+
+    let expected_output = r#"classDiagram
+    class Person {
+        + str name
+    }
+
+    class Movie {
+        <<typeddict>>
+        + str title
+        + NotRequired[int] year
+        + NotRequired[Person] director
+    }
+
+    class Point {
+        <<namedtuple>>
+        + int x
+        + int y
+    }
+
+    class Range {
+        <<namedtuple>>
+        + Any start
+        + Any stop
+        + size(self) int
+    }
+
+    Movie o-- "0..1" Person : director
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_functional_class_declarations() {
+    let source = r#"
+from collections import namedtuple
+from enum import Enum
+from typing import NamedTuple, NewType, TypedDict
+
+class User:
+    name: str
+
+Pair = namedtuple("Pair", "left, right")
+Point = NamedTuple("Point", [("x", int), ("y", int)])
+Movie = TypedDict("Movie", {"title": str, "year": int}, total=False)
+Color = Enum("Color", "RED GREEN")
+Status = Enum("Status", [("ACTIVE", "active"), ("DONE", "done")])
+UserId = NewType("UserId", User)
+not_a_class = dict(a=1)
+"#;
+
+    let expected_output = r#"classDiagram
+    class Color {
+        <<enumeration>>
+        + RED
+        + GREEN
+    }
+
+    class Movie {
+        <<typeddict>>
+        + NotRequired[str] title
+        + NotRequired[int] year
+    }
+
+    class Pair {
+        <<namedtuple>>
+        + Any left
+        + Any right
+    }
+
+    class Point {
+        <<namedtuple>>
+        + int x
+        + int y
+    }
+
+    class Status {
+        <<enumeration>>
+        + ACTIVE
+        + DONE
+    }
+
+    class User {
+        + str name
+    }
+
+    class UserId {
+        <<newtype>>
+    }
+
+    UserId --|> User
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_model_classes() {
+    let source = r#"
+from dataclasses import dataclass, field
+from typing import dataclass_transform
+
+import attrs
+import msgspec
+from pydantic import BaseModel, ConfigDict, Field
+
+class User(BaseModel):
+    model_config = ConfigDict(frozen=True)
+
+    name: str = Field(alias="userName")
+    tags: list[str] = Field(default_factory=list)
+
+class Admin(User):
+    level: int = 0
+
+@attrs.define(kw_only=True)
+class Point:
+    x: int = attrs.field(default=0, alias="px")
+    y: int = 0
+
+class Event(msgspec.Struct, frozen=True):
+    id: int = msgspec.field(name="eventId")
+
+@dataclass(frozen=True)
+class Settings:
+    debug: bool = False
+    paths: list[str] = field(default_factory=list)
+
+@dataclass_transform()
+class ModelBase: ...
+
+class Record(ModelBase):
+    key: str
+"#;
+
+    let expected_output = r#"classDiagram
+    class User {
+        <<pydantic>>
+        + str name [alias="userName", frozen]
+        + list[str] tags = list() [frozen]
+    }
+
+    class Admin {
+        <<pydantic>>
+        + int level = 0
+    }
+
+    class Event {
+        <<msgspec>>
+        + int id [alias="eventId", frozen]
+    }
+
+    class ModelBase
+
+    class Point {
+        <<attrs>>
+        + int x = 0 [alias="px", kw_only]
+        + int y = 0 [kw_only]
+    }
+
+    class Record {
+        <<dataclass>>
+        + str key
+    }
+
+    class Settings {
+        <<dataclass>>
+        + bool debug = False [frozen]
+        + list[str] paths = list() [frozen]
+    }
+
+    User --|> pydantic.BaseModel
+
+    Admin --|> User
+
+    Event --|> msgspec.Struct
+
+    Record --|> ModelBase
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_django_models() {
+    let source = r#"
+from django.db import models
+
+class Author(models.Model):
+    name = models.CharField(max_length=100)
+    mentor = models.ForeignKey("self", on_delete=models.SET_NULL, null=True)
+
+class Book(models.Model):
+    title = models.CharField(max_length=200)
+    author = models.ForeignKey(Author, on_delete=models.CASCADE)
+    tags = models.ManyToManyField("catalog.Tag")
+    published = models.DateField(null=True)
+
+    class Meta:
+        ordering = ["title"]
+
+class Tag(models.Model):
+    label = models.SlugField()
+"#;
+
+    let expected_output = r#"classDiagram
+    class Author {
+        <<django>>
+        + str name
+        + Author | None mentor
+    }
+
+    class Tag {
+        <<django>>
+        + str label
+    }
+
+    class Book {
+        <<django>>
+        + str title
+        + Author author
+        + list[Tag] tags
+        + date | None published
+    }
+
+    Author --|> django.db.models.Model
+
+    Book --|> django.db.models.Model
+
+    Tag --|> django.db.models.Model
+
+    Author --> "0..1" Author : mentor
+
+    Book --> "1" Author : author
+
+    Book --> "*" Tag : tags
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_sqlalchemy_models() {
+    let source = r#"
+from __future__ import annotations
+
+from sqlalchemy import Column, ForeignKey, Integer, String
+from sqlalchemy.orm import DeclarativeBase, Mapped, declarative_base, mapped_column, relationship
+
+class Base(DeclarativeBase):
+    pass
+
+class Parent(Base):
+    __tablename__ = "parents"
+
+    id: Mapped[int] = mapped_column(primary_key=True)
+    name: Mapped[str]
+    children: Mapped[list[Child]] = relationship(back_populates="parent")
+
+class Child(Base):
+    __tablename__ = "children"
+
+    id: Mapped[int] = mapped_column(primary_key=True)
+    parent_id: Mapped[int] = mapped_column(ForeignKey("parents.id"))
+    parent: Mapped[Parent] = relationship(back_populates="children")
+
+Legacy = declarative_base()
+
+class Tag(Legacy):
+    __tablename__ = "tags"
+
+    id = Column(Integer, primary_key=True)
+    label = Column(String(50))
+    uses = Column(Integer, default=0)
+"#;
+
+    let expected_output = r#"classDiagram
+    class Base {
+        <<sqlalchemy>>
+    }
+
+    class Parent {
+        <<sqlalchemy>>
+        + int id [PK]
+        + str name
+        + list[Child] children
+    }
+
+    class Child {
+        <<sqlalchemy>>
+        + int id [PK]
+        + int parent_id [FK parents.id]
+        + Parent parent
+    }
+
+    class Tag {
+        <<sqlalchemy>>
+        + int id [PK]
+        + str label
+        + int uses = 0
+    }
+
+    Base --|> sqlalchemy.orm.DeclarativeBase
+
+    Parent --|> Base
+
+    Child --|> Base
+
+    Tag --|> Legacy
+
+    Parent "1" <--> "*" Child : children / parent
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_er_diagram() {
+    use crate::render::mermaid_renderer::RenderOptions;
+    use crate::render::renderer::DiagramKind;
+
+    let source = r#"
+from django.db import models
+
+class Author(models.Model):
+    name = models.CharField(max_length=100)
+    mentor = models.ForeignKey("self", on_delete=models.SET_NULL, null=True)
+
+class Book(models.Model):
+    title = models.CharField(max_length=200)
+    author = models.ForeignKey(Author, on_delete=models.CASCADE)
+    tags = models.ManyToManyField("catalog.Tag")
+
+    def __str__(self) -> str:
+        return self.title
+
+class Tag(models.Model):
+    label = models.SlugField()
+"#;
+
+    let expected_output = r#"erDiagram
+    Author {
+        str name
+        Author_or_None mentor FK
+    }
+
+    Tag {
+        str label
+    }
+
+    Book {
+        str title
+        Author author FK
+    }
+
+    Author }o..o| Author : "mentor"
+    Book }o..|| Author : "author"
+    Book }o..o{ Tag : "tags"
+"#;
+
+    let mut diagram = ClassDiagram::new(RenderOptions {
+        diagram_kind: DiagramKind::Er,
+        ..RenderOptions::default()
+    });
+    diagram.add_source(source);
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output, expected_output);
+}
+
+#[test]
+fn test_forward_references_and_nested_annotations() {
+    let source = r#"
+from typing import Annotated, Literal, Optional
+
+class Wheel:
+    pass
+
+class Part:
+    pass
+
+class Engine:
+    pass
+
+class Node:
+    children: list["Node"]
+    parent: "Optional[Node]"
+
+class Car:
+    engine: "Engine"
+    boosted: Annotated[Engine, "turbo"]
+    wheels: dict[str, list[Wheel]]
+    spares: Optional[list[Part]]
+    notes: Annotated[str, "free text"]
+    body: Literal["Wheel", "Part"]
+"#;
+
+    let expected_output = r#"classDiagram
+    class Engine
+
+    class Part
+
+    class Wheel
+
+    class Car {
+        + "Engine" engine
+        + Annotated[Engine, "turbo"] boosted
+        + dict[str, list[Wheel]] wheels
+        + Optional[list[Part]] spares
+        + Annotated[str, "free text"] notes
+        + Literal["Wheel", "Part"] body
+    }
+
+    class Node {
+        + list["Node"] children
+        + "Optional[Node]" parent
+    }
+
+    Node o-- "*" Node : children
+
+    Node o-- "0..1" Node : parent
+
+    Car *-- "1" Engine : engine
+
+    Car *-- "1" Engine : boosted
+
+    Car --> "*" Wheel : wheels [str]
+
+    Car o-- "*" Part : spares
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_class_var_final_and_init_var() {
+    let source = r#"
+from dataclasses import InitVar, dataclass
+from typing import ClassVar, Final
+
+class Config:
+    instances: ClassVar[int] = 0
+    MAX_RETRIES: Final = 3
+    name: Final[str]
+
+@dataclass(kw_only=True)
+class Account:
+    registry: ClassVar[int] = 0
+    owner: str
+    password: InitVar[str]
+"#;
+
+    let expected_output = r#"classDiagram
+    class Account {
+        <<dataclass>>
+        + int registry = 0$
+        + str owner [kw_only]
+        + str password [init_only, kw_only]
+    }
+
+    class Config {
+        + int instances$
+        + int MAX_RETRIES [final]
+        + str name [final]
+    }
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_class_var_and_init_var_edges() {
+    let source = r#"
+from dataclasses import InitVar, dataclass
+from typing import ClassVar
+
+class Engine: ...
+
+class Registry: ...
+
+@dataclass
+class Car:
+    engine: Engine
+    spare: InitVar[Engine]
+    registry: ClassVar[Registry]
+"#;
+
+    let expected_output = r#"classDiagram
+    class Engine
+
+    class Registry
+
+    class Car {
+        <<dataclass>>
+        + Engine engine
+        + Engine spare [init_only]
+        + Registry registry$
+    }
+
+    Car *-- "1" Engine : engine
+
+    Car --> "1" Registry : registry
+"#;
+
+    test_diagram(source, expected_output);
+}
+
+#[test]
+fn test_notebook_source() {
+    let notebook = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": ["# Prototype"]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "metadata": {},
+   "outputs": [],
+   "source": ["%matplotlib inline\n", "!pip install numpy\n", "class Animal:\n", "    name: str"]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "metadata": {},
+   "outputs": [],
+   "source": ["class Dog(Animal):\n", "    def bark(self) -> str: ..."]
+  }
+ ],
+ "metadata": {
+  "language_info": {"name": "python"}
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+
+    let expected_output = "classDiagram
+    class Animal {
+        + str name
+    }
+
+    class Dog {
+        + bark(self) str
+    }
+
+    Dog --|> Animal
+";
+
+    let mut diagram = ClassDiagram::default();
+    diagram.add_file(notebook, Path::new("prototype.ipynb"));
+    let output = diagram.render().unwrap_or_default();
+    assert_eq!(output.trim(), expected_output.trim());
+}
+
 fn test_diagram(source: &str, expected_output: &str) {
     let mut diagram = ClassDiagram::default();
     diagram.add_source(source);
@@ -6,6 +6,7 @@ pub mod class_diagram;
 pub mod render;
 
 pub use render::mermaid_renderer::RenderOptions;
+use render::renderer::Visibility;
 pub use ruff_python_ast as ast;
 
 use class_diagram::ClassDiagram;
@@ -55,7 +56,137 @@ impl PyMermaider {
     #[wasm_bindgen(js_name = setHidePrivateMembers)]
     #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
     pub fn set_hide_private_members(&mut self, hide: bool) {
-        self.options.hide_private_members = hide;
+        self.options.min_visibility = if hide {
+            Visibility::Protected
+        } else {
+            Visibility::Private
+        };
+    }
+
+    /// Set the least visibility of the members to show (public, protected, private)
+    ///
+    /// # Errors
+    /// Returns an error if `visibility` is not one of: `public`, `protected`, `private`.
+    #[wasm_bindgen(js_name = setMinVisibility)]
+    pub fn set_min_visibility(&mut self, visibility: &str) -> Result<(), JsValue> {
+        self.options.min_visibility = visibility
+            .parse()
+            .map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Set how leading underscores map to member visibility (underscore, mangling)
+    ///
+    /// # Errors
+    /// Returns an error if `policy` is not one of: `underscore`, `mangling`.
+    #[wasm_bindgen(js_name = setVisibilityPolicy)]
+    pub fn set_visibility_policy(&mut self, policy: &str) -> Result<(), JsValue> {
+        self.options.visibility_policy =
+            policy.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Set how much of each method parameter to show (names, types, full)
+    ///
+    /// # Errors
+    /// Returns an error if `style` is not one of: `names`, `types`, `full`.
+    #[wasm_bindgen(js_name = setParameterStyle)]
+    pub fn set_parameter_style(&mut self, style: &str) -> Result<(), JsValue> {
+        self.options.parameter_style = style.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Set whether to omit the receiver (self/cls) from method signatures. Off by default.
+    #[wasm_bindgen(js_name = setHideSelf)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_hide_self(&mut self, hide: bool) {
+        self.options.hide_self = hide;
+    }
+
+    /// Set how to show the `@overload` variants of a method (collapse, expand)
+    ///
+    /// # Errors
+    /// Returns an error if `style` is not one of: `collapse`, `expand`.
+    #[wasm_bindgen(js_name = setOverloadStyle)]
+    pub fn set_overload_style(&mut self, style: &str) -> Result<(), JsValue> {
+        self.options.overload_style = style.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Set whether to show the values of enum members (e.g. `RED = 1`). Off by default.
+    #[wasm_bindgen(js_name = setShowEnumValues)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_show_enum_values(&mut self, show: bool) {
+        self.options.show_enum_values = show;
+    }
+
+    /// Set whether to show return types inferred for unannotated methods (marked `?`). Off by default.
+    #[wasm_bindgen(js_name = setInferReturnTypes)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_infer_return_types(&mut self, infer: bool) {
+        self.options.infer_return_types = infer;
+    }
+
+    /// Set whether to take missing parameter and return types from docstrings. Off by default.
+    #[wasm_bindgen(js_name = setDocstringTypes)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_docstring_types(&mut self, docstring_types: bool) {
+        self.options.docstring_types = docstring_types;
+    }
+
+    /// Set how much of each class docstring to attach as a note (none, line, paragraph)
+    ///
+    /// # Errors
+    /// Returns an error if `notes` is not one of: `none`, `line`, `paragraph`.
+    #[wasm_bindgen(js_name = setDocstrings)]
+    pub fn set_docstrings(&mut self, notes: &str) -> Result<(), JsValue> {
+        self.options.docstrings = notes.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Set the length at which docstring notes are cut, or 0 for no limit. 120 by default.
+    #[wasm_bindgen(js_name = setDocstringMaxLength)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_docstring_max_length(&mut self, length: usize) {
+        self.options.docstring_max_length = length;
+    }
+
+    /// Set the line length of docstring notes, or 0 to keep one line. 40 by default.
+    #[wasm_bindgen(js_name = setDocstringLineLength)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_docstring_line_length(&mut self, length: usize) {
+        self.options.docstring_line_length = length;
+    }
+
+    /// Set whether to merge parallel edges into one edge labelled with all their fields. Off by default.
+    #[wasm_bindgen(js_name = setMergeParallelEdges)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_merge_parallel_edges(&mut self, merge: bool) {
+        self.options.merge_parallel_edges = merge;
+    }
+
+    /// Set whether to draw dependency edges to method parameter and return types. Off by default.
+    #[wasm_bindgen(js_name = setShowDependencies)]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen prohibits const fn on exported methods
+    pub fn set_show_dependencies(&mut self, show: bool) {
+        self.options.show_dependencies = show;
+    }
+
+    /// Set the kind of diagram to render (class, er)
+    ///
+    /// # Errors
+    /// Returns an error if `kind` is not one of: `class`, `er`.
+    #[wasm_bindgen(js_name = setDiagramKind)]
+    pub fn set_diagram_kind(&mut self, kind: &str) -> Result<(), JsValue> {
+        self.options.diagram_kind = kind.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    /// Get the current diagram kind
+    #[wasm_bindgen(js_name = getDiagramKind)]
+    #[must_use]
+    pub fn get_diagram_kind(&self) -> String {
+        self.options.diagram_kind.to_string()
     }
 
     /// Process Python source code and return the Mermaid diagram as a string (or empty string if no diagram)
@@ -60,11 +60,7 @@ fn main() {
             std::process::exit(1);
         }
 
-        let options = pymermaider_wasm::render::mermaid_renderer::RenderOptions {
-            direction: mermaider.args().direction,
-            hide_private_members: mermaider.args().hide_private_members,
-        };
-        let mut diagram = class_diagram::ClassDiagram::new(options);
+        let mut diagram = class_diagram::ClassDiagram::new(mermaider.args().render_options());
         diagram.add_source(&source);
 
         vec![diagram]
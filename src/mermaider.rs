@@ -1,13 +1,13 @@
 use crate::args::Args;
 use crate::settings::FileResolverSettings;
 use pymermaider_wasm::class_diagram::ClassDiagram;
-use pymermaider_wasm::render::mermaid_renderer::RenderOptions;
 use pymermaider_wasm::render::output_format::OutputFormat;
 
 use globset::Candidate;
 use ignore::{types::TypesBuilder, WalkBuilder};
 use log::{debug, error};
-use std::path::{Path, PathBuf};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
 
 pub struct Mermaider {
     args: Args,
@@ -53,7 +53,9 @@ impl Mermaider {
         }
 
         if root.is_file() {
-            let mut diagram = self.make_mermaid(std::slice::from_ref(&root.to_path_buf()));
+            let mut files = vec![root.to_path_buf()];
+            files.extend(stub_for(root).filter(|stub| stub.is_file()));
+            let mut diagram = self.make_mermaid(&files);
             if !self.args.no_title {
                 diagram.path = root.to_string_lossy().into_owned();
             }
@@ -65,10 +67,13 @@ impl Mermaider {
 
             if self.args.multiple_files {
                 use rayon::prelude::*;
-                return parsed_files
+                return pair_with_stubs(&parsed_files)
                     .par_iter()
-                    .map(|parsed_file| {
+                    .map(|(parsed_file, stub)| {
                         let mut diagram = self.make_mermaid_for_file(parsed_file);
+                        if let Some(stub) = stub {
+                            diagram = diagram.merge(self.make_mermaid_for_file(stub));
+                        }
                         if !self.args.no_title {
                             diagram.path = parsed_file
                                 .strip_prefix(root)
@@ -98,23 +103,43 @@ impl Mermaider {
     }
 
     fn make_mermaid_for_file(&self, file: &Path) -> ClassDiagram {
-        let options = RenderOptions {
-            direction: self.args.direction,
-            hide_private_members: self.args.hide_private_members,
-        };
-        let mut diagram = ClassDiagram::new(options);
+        let mut diagram = ClassDiagram::new(self.args.render_options());
         if let Ok(source) = std::fs::read_to_string(file) {
-            diagram.add_file(&source, file);
+            diagram.add_file_with_module(&source, file, &self.module_path(file));
         }
         diagram
     }
 
+    /// Dotted module path of a file relative to the project root, e.g. `["pkg", "models"]` for
+    /// `pkg/models.py`. When the project root is itself inside a package, the enclosing package
+    /// names are prepended so that absolute imports line up with the qualified class names.
+    fn module_path(&self, file: &Path) -> Vec<String> {
+        let root = self.file_settings.project_root.as_path();
+        let base = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+
+        let mut module_path = enclosing_packages(base);
+        let relative = file.strip_prefix(base).unwrap_or(file);
+        if let Some(parent) = relative.parent() {
+            module_path.extend(parent.components().filter_map(|component| match component {
+                Component::Normal(name) => Some(package_name(&name.to_string_lossy()).to_owned()),
+                _ => None,
+            }));
+        }
+        if let Some(stem) = file.file_stem().and_then(|stem| stem.to_str()) {
+            if stem != "__init__" {
+                module_path.push(stem.to_owned());
+            }
+        }
+        module_path
+    }
+
     fn make_mermaid(&self, parsed_files: &[PathBuf]) -> ClassDiagram {
         use rayon::prelude::*;
-        let options = RenderOptions {
-            direction: self.args.direction,
-            hide_private_members: self.args.hide_private_members,
-        };
+        let options = self.args.render_options();
         let per_file: Vec<ClassDiagram> = parsed_files
             .par_iter()
             .map(|file| self.make_mermaid_for_file(file))
@@ -127,9 +152,19 @@ impl Mermaider {
     fn parse_folder(&self, path: &Path) -> Vec<PathBuf> {
         let mut parsed_files = vec![];
 
-        let types = TypesBuilder::new()
-            .add_defaults()
-            .select("python")
+        let mut types = TypesBuilder::new();
+        types.add_defaults();
+        types
+            .add("pyi", "*.pyi")
+            .expect("Failed to add Python stub type");
+        types
+            .add("ipynb", "*.ipynb")
+            .expect("Failed to add Jupyter notebook type");
+        if !self.args.stubs_only {
+            types.select("python").select("ipynb");
+        }
+        let types = types
+            .select("pyi")
             .build()
             .expect("Failed to build Python types");
 
@@ -180,9 +215,66 @@ impl Mermaider {
     }
 }
 
+/// The stub file that sits next to a `.py` file (`foo.py` → `foo.pyi`), if `file` is one.
+fn stub_for(file: &Path) -> Option<PathBuf> {
+    (file.extension()? == "py").then(|| file.with_extension("pyi"))
+}
+
+/// Pair each file with the stub next to it. Stubs with an implementation are not returned on
+/// their own, so that each module yields one diagram.
+fn pair_with_stubs(files: &[PathBuf]) -> Vec<(PathBuf, Option<PathBuf>)> {
+    let all: HashSet<&PathBuf> = files.iter().collect();
+    let stubs: HashSet<PathBuf> = files
+        .iter()
+        .filter_map(|file| stub_for(file))
+        .filter(|stub| all.contains(stub))
+        .collect();
+
+    files
+        .iter()
+        .filter(|file| !stubs.contains(*file))
+        .map(|file| {
+            let stub = stub_for(file).filter(|stub| stubs.contains(stub));
+            (file.clone(), stub)
+        })
+        .collect()
+}
+
+/// Import name of a package directory; PEP 561 stub-only packages are named `<package>-stubs`.
+fn package_name(dir_name: &str) -> &str {
+    dir_name.strip_suffix("-stubs").unwrap_or(dir_name)
+}
+
+/// Names of the packages enclosing `dir` (including `dir` itself), outermost first.
+fn enclosing_packages(dir: &Path) -> Vec<String> {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+
+    let mut packages = vec![];
+    let mut current = dir.canonicalize().ok();
+    while let Some(dir) = current {
+        if !dir.join("__init__.py").is_file() && !dir.join("__init__.pyi").is_file() {
+            break;
+        }
+        let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+            break;
+        };
+        packages.push(package_name(name).to_owned());
+        current = dir.parent().map(Path::to_path_buf);
+    }
+    packages.reverse();
+    packages
+}
+
 #[cfg(test)]
 mod tests {
-    use pymermaider_wasm::render::renderer::DiagramDirection;
+    use pymermaider_wasm::render::renderer::{
+        DiagramDirection, DiagramKind, DocstringNotes, OverloadStyle, ParameterStyle, Visibility,
+        VisibilityPolicy,
+    };
     use ruff_linter::settings::types::{FilePattern, FilePatternSet, GlobPath};
     use std::io::Write as _;
     use std::path::Path;
@@ -207,9 +299,24 @@ mod tests {
             exclude: None,
             extend_exclude: None,
             include: None,
+            diagram: DiagramKind::default(),
             direction: DiagramDirection::default(),
             no_title: false,
             hide_private_members: false,
+            min_visibility: Visibility::Private,
+            visibility_policy: VisibilityPolicy::default(),
+            parameter_style: ParameterStyle::default(),
+            hide_self: false,
+            overload_style: OverloadStyle::default(),
+            show_enum_values: false,
+            infer_return_types: false,
+            docstring_types: false,
+            docstrings: DocstringNotes::default(),
+            docstring_max_length: 120,
+            docstring_line_length: 40,
+            merge_parallel_edges: false,
+            show_dependencies: false,
+            stubs_only: false,
         }
     }
 
@@ -389,6 +496,144 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_same_named_classes_in_different_packages() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        for package in ["api", "db"] {
+            let package_dir = temp.path().join(package);
+            std::fs::create_dir_all(&package_dir)?;
+            std::fs::File::create(package_dir.join("__init__.py"))?;
+            std::fs::File::create(package_dir.join("settings.py"))?
+                .write_all(b"class Config:\n    debug: bool\n")?;
+        }
+
+        let mermaider = Mermaider::new(default_args(), default_settings(temp.path()));
+        let diagrams = mermaider.generate_diagrams();
+        assert_eq!(diagrams.len(), 1);
+        let rendered = diagrams[0].render().unwrap();
+        assert!(rendered.contains("class api_settings_Config[\"Config\"] {"));
+        assert!(rendered.contains("class db_settings_Config[\"Config\"] {"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_import_resolves_to_project_class() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        let app_dir = temp.path().join("app");
+        std::fs::create_dir_all(&app_dir)?;
+        std::fs::File::create(app_dir.join("__init__.py"))?;
+        std::fs::File::create(app_dir.join("models.py"))?.write_all(b"class User: ...\n")?;
+        std::fs::File::create(app_dir.join("admin.py"))?
+            .write_all(b"from .models import User\n\nclass Admin(User):\n    delegate: User\n")?;
+        let legacy_dir = temp.path().join("legacy");
+        std::fs::create_dir_all(&legacy_dir)?;
+        std::fs::File::create(legacy_dir.join("models.py"))?.write_all(b"class User: ...\n")?;
+
+        let mermaider = Mermaider::new(default_args(), default_settings(temp.path()));
+        let diagrams = mermaider.generate_diagrams();
+        assert_eq!(diagrams.len(), 1);
+        let rendered = diagrams[0].render().unwrap();
+        assert!(rendered.contains("class app_models_User[\"User\"]"));
+        assert!(rendered.contains("class legacy_models_User[\"User\"]"));
+        assert!(rendered.contains("Admin --|> app_models_User"));
+        assert!(rendered.contains("Admin *-- \"1\" app_models_User : delegate"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_django_foreign_key_resolves_imported_model() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        for app in ["accounts", "blog", "legacy"] {
+            let app_dir = temp.path().join(app);
+            std::fs::create_dir_all(&app_dir)?;
+            std::fs::File::create(app_dir.join("__init__.py"))?;
+        }
+        std::fs::File::create(temp.path().join("accounts").join("models.py"))?.write_all(
+            b"from django.db import models\n\nclass User(models.Model):\n    name = models.CharField(max_length=100)\n",
+        )?;
+        std::fs::File::create(temp.path().join("blog").join("models.py"))?.write_all(
+            b"from django.db import models\n\nfrom accounts.models import User\n\nclass Post(models.Model):\n    author = models.ForeignKey(User, on_delete=models.CASCADE)\n",
+        )?;
+        std::fs::File::create(temp.path().join("legacy").join("models.py"))?
+            .write_all(b"class User: ...\n")?;
+
+        let mermaider = Mermaider::new(default_args(), default_settings(temp.path()));
+        let diagrams = mermaider.generate_diagrams();
+        assert_eq!(diagrams.len(), 1);
+        let rendered = diagrams[0].render().unwrap();
+        assert!(rendered.contains("+ User author"));
+        assert!(rendered.contains("Post --> \"1\" accounts_models_User : author"));
+        assert!(!rendered.contains("legacy_models_User : author"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stub_merged_with_implementation() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        std::fs::File::create(temp.path().join("client.py"))?.write_all(
+            b"class Client:\n    def __init__(self, name):\n        self.name = name\n\n    def get(self, url):\n        ...\n\n    def _retry(self):\n        ...\n",
+        )?;
+        std::fs::File::create(temp.path().join("client.pyi"))?.write_all(
+            b"class Client:\n    name: str\n    def __init__(self, name: str) -> None: ...\n    def get(self, url: str) -> bytes: ...\n",
+        )?;
+
+        for multiple_files in [false, true] {
+            let mut args = default_args();
+            args.multiple_files = multiple_files;
+            let mermaider = Mermaider::new(args, default_settings(temp.path()));
+            let diagrams = mermaider.generate_diagrams();
+            assert_eq!(diagrams.len(), 1);
+            let rendered = diagrams[0].render().unwrap();
+            assert_eq!(rendered.matches("class Client").count(), 1);
+            assert!(rendered.contains("+ str name"));
+            assert!(rendered.contains("+ get(self, url) bytes"));
+            assert!(rendered.contains("- \\_retry(self)"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stubs_only() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        std::fs::File::create(temp.path().join("setup.py"))?.write_all(b"class Command: ...")?;
+        let stubs_dir = temp.path().join("requests-stubs");
+        std::fs::create_dir_all(&stubs_dir)?;
+        std::fs::File::create(stubs_dir.join("__init__.pyi"))?;
+        std::fs::File::create(stubs_dir.join("sessions.pyi"))?
+            .write_all(b"class Session:\n    def close(self) -> None: ...\n")?;
+
+        let mut args = default_args();
+        args.stubs_only = true;
+        let mermaider = Mermaider::new(args, default_settings(temp.path()));
+        let diagrams = mermaider.generate_diagrams();
+        assert_eq!(diagrams.len(), 1);
+        let rendered = diagrams[0].render().unwrap();
+        assert!(rendered.contains("class Session"));
+        assert!(!rendered.contains("class Command"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_notebook_discovered() -> Result<()> {
+        init_logger();
+        let temp = TempDir::new()?;
+        std::fs::File::create(temp.path().join("analysis.ipynb"))?.write_all(
+            br#"{"cells": [{"cell_type": "code", "execution_count": null, "metadata": {}, "outputs": [], "source": ["%load_ext autoreload\n", "class Model: ..."]}], "metadata": {}, "nbformat": 4, "nbformat_minor": 5}"#,
+        )?;
+
+        let mermaider = Mermaider::new(default_args(), default_settings(temp.path()));
+        let diagrams = mermaider.generate_diagrams();
+        assert_eq!(diagrams.len(), 1);
+        let rendered = diagrams[0].render().unwrap();
+        assert!(rendered.contains("class Model"));
+        Ok(())
+    }
+
     #[test]
     fn test_parallel_processing_is_deterministic() -> Result<()> {
         init_logger();
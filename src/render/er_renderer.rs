@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::mermaid_escape::MermaidEscape;
+use super::mermaid_renderer::{
+    diagram_compositions, render_header_for, sanitize_mermaid_id, MermaidIds, RenderOptions,
+};
+use super::renderer::{
+    Attribute, Cardinality, ClassNode, CompositionEdge, CompositionKind, Diagram,
+};
+
+const TAB: &str = "    ";
+
+/// Crow's foot marker for the far end of a relationship, e.g. `o{` for "zero or more".
+const fn right_marker(cardinality: Cardinality) -> &'static str {
+    match cardinality {
+        Cardinality::One => "||",
+        Cardinality::ZeroOrOne => "o|",
+        Cardinality::Many => "o{",
+    }
+}
+
+/// Crow's foot marker for the near end of a relationship, mirrored from [`right_marker`].
+const fn left_marker(cardinality: Cardinality) -> &'static str {
+    match cardinality {
+        Cardinality::One => "||",
+        Cardinality::ZeroOrOne => "|o",
+        Cardinality::Many => "}o",
+    }
+}
+
+/// Mermaid only accepts word characters and brackets in attribute types, so `str | None`
+/// becomes `str_or_None` and `dict[str, int]` becomes `dict[str-int]`.
+fn er_type(type_annotation: &str) -> String {
+    let mut output = String::with_capacity(type_annotation.len());
+    for c in type_annotation.chars() {
+        match c {
+            '"' | '\'' | ' ' => {}
+            '|' => output.push_str("_or_"),
+            ',' => output.push('-'),
+            c if c.is_alphanumeric() || matches!(c, '_' | '[' | ']' | '(' | ')' | '-') => {
+                output.push(c);
+            }
+            _ => output.push('_'),
+        }
+    }
+    if output.is_empty() {
+        output.push_str("Any");
+    }
+    output
+}
+
+/// How an attribute takes part in the class's relationships
+#[derive(Clone, Copy)]
+enum Reference {
+    /// Holds one related entity, drawn as a foreign key
+    ToOne,
+    /// Holds a collection of related entities, drawn only as the relationship
+    ToMany,
+}
+
+fn render_attribute(output: &mut String, attr: &Attribute, reference: Option<Reference>) {
+    let _ = write!(
+        output,
+        "{TAB}{TAB}{} {}",
+        er_type(&attr.type_annotation),
+        attr.name
+    );
+
+    let mut keys = Vec::new();
+    if attr.is_primary_key() {
+        keys.push("PK");
+    }
+    if attr.foreign_key().is_some() || matches!(reference, Some(Reference::ToOne)) {
+        keys.push("FK");
+    }
+    if !keys.is_empty() {
+        let _ = write!(output, " {}", keys.join(", "));
+    }
+    if let Some(column) = attr.foreign_key() {
+        let _ = write!(output, " \"{column}\"");
+    }
+    output.push('\n');
+}
+
+/// Render an entity with its attributes.
+///
+/// Attributes that hold related entities are drawn as relationships. A reference to a single
+/// entity stays as an `FK` attribute, unless the class declares its foreign key columns
+/// itself (e.g. SQLAlchemy's `parent_id` next to `parent`); collections are left out.
+fn render_entity(
+    class: &ClassNode,
+    entity_id: &str,
+    label: Option<&str>,
+    references: &HashMap<&str, Reference>,
+    opts: &RenderOptions,
+) -> String {
+    let has_foreign_keys = class
+        .attributes
+        .iter()
+        .any(|attr| attr.foreign_key().is_some());
+    let attributes: Vec<(&Attribute, Option<Reference>)> = class
+        .attributes
+        .iter()
+        .filter(|attr| opts.shows(attr.visibility))
+        // Class variables, init-only pseudo-fields and enum members are not stored per row
+        .filter(|attr| !attr.is_static && !attr.is_init_var && !attr.is_enum_member)
+        .map(|attr| (attr, references.get(attr.name.as_str()).copied()))
+        .filter(|(_, reference)| match reference {
+            Some(Reference::ToMany) => false,
+            Some(Reference::ToOne) => !has_foreign_keys,
+            None => true,
+        })
+        .collect();
+
+    let mut output = format!("{TAB}{entity_id}");
+    if let Some(label) = label {
+        let _ = write!(output, "[\"{}\"]", label.escape_entities());
+    }
+    if !attributes.is_empty() {
+        output.push_str(" {\n");
+        for (attr, reference) in attributes {
+            render_attribute(&mut output, attr, reference);
+        }
+        let _ = write!(output, "{TAB}}}");
+    }
+    output.push_str("\n\n");
+    output
+}
+
+/// Render a relationship with crow's foot cardinality on both ends.
+///
+/// The far end comes from the annotation (`list[X]` is zero or more, `Optional[X]` zero or
+/// one). The near end is known for `back_populates` pairs; otherwise a composed part belongs
+/// to exactly one owner and anything else may be shared by many.
+#[must_use]
+pub fn render_relationship(composition: &CompositionEdge) -> String {
+    let left = match (composition.inverse_cardinality, composition.kind) {
+        (Some(inverse), _) => left_marker(inverse),
+        (None, CompositionKind::Composition) => left_marker(Cardinality::One),
+        (None, _) => left_marker(Cardinality::Many),
+    };
+    let line = if composition.kind == CompositionKind::Composition {
+        "--"
+    } else {
+        ".."
+    };
+
+    let mut label = composition.fields.join(", ");
+    if let (Some(_), Some(back_populates)) =
+        (composition.inverse_cardinality, &composition.back_populates)
+    {
+        let _ = write!(label, " / {back_populates}");
+    }
+    if let Some(ref qualifier) = composition.qualifier {
+        let _ = write!(label, " [{qualifier}]");
+    }
+
+    format!(
+        "{TAB}{} {left}{line}{} {} : \"{}\"\n",
+        composition.container,
+        right_marker(composition.cardinality),
+        sanitize_mermaid_id(&composition.contained.name),
+        label.trim()
+    )
+}
+
+/// Render a full Mermaid entity-relationship diagram. Inheritance, dependencies and methods
+/// have no place in it and are left out.
+#[must_use]
+pub fn render_er_diagram(
+    diagram: &Diagram,
+    title: Option<&str>,
+    opts: &RenderOptions,
+) -> Option<String> {
+    if diagram.is_empty() {
+        return None;
+    }
+
+    let mut output = String::with_capacity(1024);
+    output.push_str(&render_header_for("erDiagram", title, opts.direction));
+
+    // The attributes of each class that hold related entities
+    let mut references: HashMap<&str, HashMap<&str, Reference>> = HashMap::new();
+    for comp in &diagram.compositions {
+        let reference = if comp.cardinality == Cardinality::Many {
+            Reference::ToMany
+        } else {
+            Reference::ToOne
+        };
+        let class_references = references.entry(comp.container.as_str()).or_default();
+        for field in &comp.fields {
+            class_references.insert(field.as_str(), reference);
+        }
+    }
+
+    let classes = diagram.classes_topologically_sorted_unique();
+    let ids = MermaidIds::new(&classes, diagram.class_index());
+    let no_references = HashMap::new();
+    for class in classes {
+        output.push_str(&render_entity(
+            class,
+            &ids.class_id(&class.id),
+            ids.label(class),
+            references.get(class.id.as_str()).unwrap_or(&no_references),
+            opts,
+        ));
+    }
+
+    for comp in diagram_compositions(diagram, &ids, opts) {
+        output.push_str(&render_relationship(&comp));
+    }
+
+    let trimmed_len = output.trim_end().len();
+    output.truncate(trimmed_len);
+    output.push('\n');
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::renderer::{ClassType, Visibility};
+
+    #[test]
+    fn test_er_type() {
+        assert_eq!(er_type("int"), "int");
+        assert_eq!(er_type("str | None"), "str_or_None");
+        assert_eq!(er_type("dict[str, int]"), "dict[str-int]");
+        assert_eq!(er_type("list[\"Child\"]"), "list[Child]");
+        assert_eq!(er_type("datetime.date"), "datetime_date");
+    }
+
+    #[test]
+    fn test_render_er_diagram() {
+        let class = |name: &str, attributes| ClassNode {
+            id: name.to_string(),
+            name: name.to_string(),
+            type_params: None,
+            class_type: ClassType::SqlAlchemyModel,
+            attributes,
+            methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
+        };
+
+        let mut diagram = Diagram::new();
+        diagram.add_class(class(
+            "Parent",
+            vec![
+                Attribute {
+                    modifiers: vec!["PK".to_string()],
+                    ..Attribute::new("id", "int", Visibility::Public)
+                },
+                Attribute::new("children", "list[\"Child\"]", Visibility::Public),
+            ],
+        ));
+        diagram.add_class(class(
+            "Child",
+            vec![
+                Attribute {
+                    modifiers: vec!["PK".to_string()],
+                    ..Attribute::new("id", "int", Visibility::Public)
+                },
+                Attribute {
+                    modifiers: vec!["FK parents.id".to_string()],
+                    ..Attribute::new("parent_id", "int", Visibility::Public)
+                },
+                Attribute::new("parent", "\"Parent\"", Visibility::Public),
+            ],
+        ));
+        let edge = |container: &str, contained: &str, field: &str, back: &str, cardinality| {
+            CompositionEdge {
+                container: container.to_string(),
+                contained: contained.into(),
+                kind: CompositionKind::Association,
+                cardinality,
+                qualifier: None,
+                fields: vec![field.to_string()],
+                back_populates: Some(back.to_string()),
+                inverse_cardinality: None,
+            }
+        };
+        diagram.add_composition(edge(
+            "Parent",
+            "Child",
+            "children",
+            "parent",
+            Cardinality::Many,
+        ));
+        diagram.add_composition(edge(
+            "Child",
+            "Parent",
+            "parent",
+            "children",
+            Cardinality::One,
+        ));
+
+        let expected = r#"erDiagram
+    Parent {
+        int id PK
+    }
+
+    Child {
+        int id PK
+        int parent_id FK "parents.id"
+    }
+
+    Parent ||..o{ Child : "children / parent"
+"#;
+        assert_eq!(
+            render_er_diagram(&diagram, None, &RenderOptions::default()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_render_relationship_without_inverse() {
+        let edge = |kind, cardinality| CompositionEdge {
+            container: "Book".to_string(),
+            contained: "Author".into(),
+            kind,
+            cardinality,
+            qualifier: None,
+            fields: vec!["author".to_string()],
+            back_populates: None,
+            inverse_cardinality: None,
+        };
+
+        assert_eq!(
+            render_relationship(&edge(CompositionKind::Association, Cardinality::One)),
+            "    Book }o..|| Author : \"author\"\n"
+        );
+        assert_eq!(
+            render_relationship(&edge(CompositionKind::Composition, Cardinality::ZeroOrOne)),
+            "    Book ||--o| Author : \"author\"\n"
+        );
+    }
+}
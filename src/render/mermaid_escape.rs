@@ -3,6 +3,11 @@ pub trait MermaidEscape {
     /// Escape leading underscores for Mermaid diagrams.
     /// Mermaid interprets __ as formatting, so we escape leading underscores with backslashes.
     fn escape_underscores(&self) -> String;
+
+    /// Escape characters for a quoted Mermaid string such as a note.
+    /// Quotes would end the string and angle brackets be read as markup, so these (and the `#`
+    /// and `\` that start entity codes and line breaks) are written as entity codes like `#quot;`.
+    fn escape_entities(&self) -> String;
 }
 
 impl MermaidEscape for str {
@@ -18,6 +23,21 @@ impl MermaidEscape for str {
             self.to_owned()
         }
     }
+
+    fn escape_entities(&self) -> String {
+        let mut output = String::with_capacity(self.len());
+        for c in self.chars() {
+            match c {
+                '"' => output.push_str("#quot;"),
+                '#' => output.push_str("#35;"),
+                '<' => output.push_str("#lt;"),
+                '>' => output.push_str("#gt;"),
+                '\\' => output.push_str("#92;"),
+                c => output.push(c),
+            }
+        }
+        output
+    }
 }
 
 #[cfg(test)]
@@ -48,4 +68,13 @@ mod tests {
     fn test_trailing_underscores_not_escaped() {
         assert_eq!("_method_".escape_underscores(), r"\_method_");
     }
+
+    #[test]
+    fn test_escape_entities() {
+        assert_eq!("plain text".escape_entities(), "plain text");
+        assert_eq!(
+            r#"Say "hi" to <b>#1</b> \n"#.escape_entities(),
+            "Say #quot;hi#quot; to #lt;b#gt;#35;1#lt;/b#gt; #92;n"
+        );
+    }
 }
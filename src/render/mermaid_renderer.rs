@@ -1,18 +1,78 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 
 use super::mermaid_escape::MermaidEscape;
 use super::renderer::{
-    Attribute, ClassNode, ClassType, CompositionEdge, Diagram, DiagramDirection, MethodSignature,
-    RelationType, RelationshipEdge, Visibility,
+    Attribute, ClassIndex, ClassNode, ClassRef, ClassType, CompositionEdge, CompositionKind,
+    Diagram, DiagramDirection, DiagramKind, DocstringNotes, MethodParameter, MethodSignature,
+    OverloadStyle, ParameterKind, ParameterStyle, RelationType, RelationshipEdge, Visibility,
+    VisibilityPolicy,
 };
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 
 const TAB: &str = "    ";
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy)]
 pub struct RenderOptions {
     pub direction: DiagramDirection,
-    pub hide_private_members: bool,
+    /// Hide members less visible than this, e.g. `Protected` hides private members.
+    pub min_visibility: Visibility,
+    /// How leading underscores map to member visibility.
+    pub visibility_policy: VisibilityPolicy,
+    pub parameter_style: ParameterStyle,
+    /// Omit the receiver (`self`/`cls`) from method signatures.
+    pub hide_self: bool,
+    /// Collapse the `@overload` variants of a method into one entry or list each of them.
+    pub overload_style: OverloadStyle,
+    /// Show the values of enum members, e.g. `RED = 1`.
+    pub show_enum_values: bool,
+    /// Show the return types inferred for unannotated methods, marked with a trailing `?`.
+    pub infer_return_types: bool,
+    /// Take the types of unannotated parameters and return values from docstrings.
+    pub docstring_types: bool,
+    /// Attach the first line or paragraph of each class docstring as a note.
+    pub docstrings: DocstringNotes,
+    /// Cut notes longer than this many characters, or 0 to keep them whole.
+    pub docstring_max_length: usize,
+    /// Break notes into lines of at most this many characters, or 0 to keep one line.
+    pub docstring_line_length: usize,
+    /// Draw one edge per related class pair, labelled with all the fields that create it.
+    pub merge_parallel_edges: bool,
+    /// Draw dependency edges to the types accepted and returned by methods.
+    pub show_dependencies: bool,
+    /// Render a class diagram or an entity-relationship diagram.
+    pub diagram_kind: DiagramKind,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            direction: DiagramDirection::default(),
+            min_visibility: Visibility::Private,
+            visibility_policy: VisibilityPolicy::default(),
+            parameter_style: ParameterStyle::default(),
+            hide_self: false,
+            overload_style: OverloadStyle::default(),
+            show_enum_values: false,
+            infer_return_types: false,
+            docstring_types: false,
+            docstrings: DocstringNotes::default(),
+            docstring_max_length: 120,
+            docstring_line_length: 40,
+            merge_parallel_edges: false,
+            show_dependencies: false,
+            diagram_kind: DiagramKind::default(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Whether members with the given visibility are drawn.
+    #[must_use]
+    pub const fn shows(&self, visibility: Visibility) -> bool {
+        visibility.is_at_least(self.min_visibility)
+    }
 }
 
 fn indent(indent_level: usize) -> String {
@@ -33,22 +93,32 @@ const fn format_class_type(class_type: ClassType) -> Option<&'static str> {
         ClassType::Abstract => Some("<<abstract>>"),
         ClassType::Interface => Some("<<interface>>"),
         ClassType::Enumeration => Some("<<enumeration>>"),
+        ClassType::IntEnum => Some("<<intenum>>"),
+        ClassType::StrEnum => Some("<<strenum>>"),
+        ClassType::Flag => Some("<<flag>>"),
         ClassType::Dataclass => Some("<<dataclass>>"),
         ClassType::Final => Some("<<final>>"),
+        ClassType::NamedTuple => Some("<<namedtuple>>"),
+        ClassType::TypedDict => Some("<<typeddict>>"),
+        ClassType::NewType => Some("<<newtype>>"),
+        ClassType::PydanticModel => Some("<<pydantic>>"),
+        ClassType::AttrsClass => Some("<<attrs>>"),
+        ClassType::MsgspecStruct => Some("<<msgspec>>"),
+        ClassType::DjangoModel => Some("<<django>>"),
+        ClassType::SqlAlchemyModel => Some("<<sqlalchemy>>"),
     }
 }
 
 fn has_class_body(class: &ClassNode, opts: RenderOptions) -> bool {
-    let hide = opts.hide_private_members;
     let n_attrs = class
         .attributes
         .iter()
-        .filter(|a| !hide || a.visibility != Visibility::Private)
+        .filter(|a| opts.shows(a.visibility))
         .count();
     let n_methods = class
         .methods
         .iter()
-        .filter(|m| !hide || m.visibility != Visibility::Private)
+        .filter(|m| opts.shows(m.visibility))
         .count();
     n_attrs > 0 || n_methods > 0 || class.class_type != ClassType::Regular
 }
@@ -61,6 +131,16 @@ fn render_class_annotation(output: &mut String, inner_indent: &str, class_type:
     }
 }
 
+/// Braces would close the class body early, so values containing them fall back to the
+/// stub-style `...`.
+fn brace_safe(value: &str) -> &str {
+    if value.contains(['{', '}']) {
+        "..."
+    } else {
+        value
+    }
+}
+
 fn render_attribute(output: &mut String, inner_indent: &str, attr: &Attribute) {
     output.push_str(inner_indent);
     output.push(format_visibility(attr.visibility));
@@ -68,10 +148,228 @@ fn render_attribute(output: &mut String, inner_indent: &str, attr: &Attribute) {
     output.push_str(&attr.type_annotation);
     output.push(' ');
     output.push_str(&attr.name.escape_underscores());
+    if let Some(ref default) = attr.default {
+        output.push_str(" = ");
+        output.push_str(brace_safe(default));
+    }
+    let qualifiers = [(attr.is_final, "final"), (attr.is_init_var, "init_only")]
+        .into_iter()
+        .filter_map(|(enabled, qualifier)| enabled.then_some(qualifier));
+    let modifiers: Vec<&str> = qualifiers
+        .chain(attr.modifiers.iter().map(|m| brace_safe(m)))
+        .collect();
+    if !modifiers.is_empty() {
+        let _ = write!(output, " [{}]", modifiers.join(", "));
+    }
+    // Mermaid underlines static members
+    if attr.is_static {
+        output.push('$');
+    }
     output.push('\n');
 }
 
-fn render_method(output: &mut String, inner_indent: &str, method: &MethodSignature) {
+/// Render an enum member by name, without a type, and with its value if asked for
+/// (`+ RED = 1`).
+fn render_enum_member(output: &mut String, inner_indent: &str, attr: &Attribute, show_value: bool) {
+    output.push_str(inner_indent);
+    output.push(format_visibility(attr.visibility));
+    output.push(' ');
+    output.push_str(&attr.name.escape_underscores());
+    if let Some(value) = attr.default.as_deref().filter(|_| show_value) {
+        output.push_str(" = ");
+        output.push_str(brace_safe(value));
+    }
+    output.push('\n');
+}
+
+fn render_parameter(parameter: &MethodParameter, style: ParameterStyle) -> String {
+    let mut output = String::new();
+    match parameter.kind {
+        ParameterKind::VarPositional => output.push('*'),
+        ParameterKind::VarKeyword => output.push_str("**"),
+        _ => {}
+    }
+    output.push_str(&parameter.name);
+
+    let type_annotation = parameter
+        .type_annotation
+        .as_ref()
+        .filter(|_| style != ParameterStyle::Names);
+    if let Some(type_annotation) = type_annotation {
+        output.push_str(": ");
+        output.push_str(type_annotation);
+    }
+
+    if let Some(default) = parameter
+        .default
+        .as_ref()
+        .filter(|_| style == ParameterStyle::Full)
+    {
+        let default = brace_safe(default);
+        output.push_str(if type_annotation.is_some() {
+            " = "
+        } else {
+            "="
+        });
+        output.push_str(default);
+    }
+
+    output
+}
+
+fn render_parameters(output: &mut String, method: &MethodSignature, opts: &RenderOptions) {
+    let skip_receiver = opts.hide_self && !method.is_static;
+    let mut parts: Vec<String> = Vec::with_capacity(method.parameters.len());
+    let mut seen_star = false;
+
+    for (idx, parameter) in method.parameters.iter().enumerate() {
+        match parameter.kind {
+            ParameterKind::KeywordOnly if !seen_star => {
+                parts.push("*".to_owned());
+                seen_star = true;
+            }
+            ParameterKind::VarPositional => seen_star = true,
+            _ => {}
+        }
+
+        let is_receiver = idx == 0
+            && matches!(
+                parameter.kind,
+                ParameterKind::PositionalOnly | ParameterKind::PositionalOrKeyword
+            );
+        if !(skip_receiver && is_receiver) {
+            parts.push(render_parameter(parameter, opts.parameter_style));
+        }
+
+        let ends_positional_only = parameter.kind == ParameterKind::PositionalOnly
+            && method
+                .parameters
+                .get(idx + 1)
+                .is_none_or(|next| next.kind != ParameterKind::PositionalOnly);
+        if ends_positional_only && !parts.is_empty() {
+            parts.push("/".to_owned());
+        }
+    }
+
+    output.push_str(&parts.join(", "));
+}
+
+/// Add the members of a union annotation (`bytes | None`) that `types` does not have yet.
+fn push_union_members<'a>(types: &mut Vec<&'a str>, type_annotation: Option<&'a str>) {
+    let Some(type_annotation) = type_annotation else {
+        return;
+    };
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, c) in type_annotation
+        .char_indices()
+        .chain([(type_annotation.len(), '|')])
+    {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => {
+                let member = type_annotation[start..idx].trim();
+                if !types.contains(&member) {
+                    types.push(member);
+                }
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Summarize the `@overload` variants of a method as one signature. Parameters are matched by
+/// name across the overloads and annotated with the union of their types, as is the return
+/// type, e.g. `get(key: int | str) bytes | None`. The overloads must take the same parameters.
+fn collapse_overloads(overloads: &[&MethodSignature]) -> MethodSignature {
+    let union = |types: &[&str]| (!types.is_empty()).then(|| types.join(" | "));
+
+    let mut parameters: IndexMap<&str, (&MethodParameter, Vec<&str>)> = IndexMap::new();
+    let mut return_types = Vec::new();
+    for overload in overloads {
+        for parameter in &overload.parameters {
+            let (_, types) = parameters
+                .entry(parameter.name.as_str())
+                .or_insert_with(|| (parameter, Vec::new()));
+            push_union_members(types, parameter.type_annotation.as_deref());
+        }
+        push_union_members(&mut return_types, overload.return_type.as_deref());
+    }
+
+    let parameters: Vec<MethodParameter> = parameters
+        .into_values()
+        .map(|(parameter, types)| MethodParameter {
+            type_annotation: union(&types),
+            ..parameter.clone()
+        })
+        .collect();
+
+    MethodSignature {
+        parameters,
+        return_type: union(&return_types),
+        ..overloads[0].clone()
+    }
+}
+
+/// Whether the overloads take the same parameters, by name and kind, so that one signature
+/// can stand for all of them.
+fn take_same_parameters(overloads: &[&MethodSignature]) -> bool {
+    overloads.windows(2).all(|pair| {
+        pair[0].parameters.len() == pair[1].parameters.len()
+            && pair[0]
+                .parameters
+                .iter()
+                .zip(&pair[1].parameters)
+                .all(|(left, right)| left.name == right.name && left.kind == right.kind)
+    })
+}
+
+/// The methods to draw, in order. The implementation of an overloaded method is covered by
+/// its `@overload` variants, which are either collapsed into one entry in place of the first
+/// of them or listed as they are. Overloads that take different parameters are always listed,
+/// as a collapsed entry could not tell which of its parameters are optional.
+fn arrange_overloads(
+    methods: &[MethodSignature],
+    style: OverloadStyle,
+) -> Vec<Cow<'_, MethodSignature>> {
+    let overloaded: HashSet<&str> = methods
+        .iter()
+        .filter(|method| method.is_overload())
+        .map(|method| method.name.as_str())
+        .collect();
+
+    let mut collapsed = HashSet::new();
+    let mut arranged = Vec::with_capacity(methods.len());
+    for method in methods {
+        if !overloaded.contains(method.name.as_str()) {
+            arranged.push(Cow::Borrowed(method));
+        } else if !method.is_overload() {
+            // The implementation's signature only repeats the overloads
+        } else if style == OverloadStyle::Expand {
+            arranged.push(Cow::Borrowed(method));
+        } else if collapsed.insert(method.name.as_str()) {
+            let overloads: Vec<&MethodSignature> = methods
+                .iter()
+                .filter(|other| other.name == method.name && other.is_overload())
+                .collect();
+            if take_same_parameters(&overloads) {
+                arranged.push(Cow::Owned(collapse_overloads(&overloads)));
+            } else {
+                arranged.extend(overloads.into_iter().map(Cow::Borrowed));
+            }
+        }
+    }
+    arranged
+}
+
+fn render_method(
+    output: &mut String,
+    inner_indent: &str,
+    method: &MethodSignature,
+    opts: &RenderOptions,
+) {
     output.push_str(inner_indent);
     output.push(format_visibility(method.visibility));
     output.push(' ');
@@ -90,13 +388,19 @@ fn render_method(output: &mut String, inner_indent: &str, method: &MethodSignatu
     // Method signature
     output.push_str(&method.name.escape_underscores());
     output.push('(');
-    output.push_str(&method.parameters);
+    render_parameters(output, method, opts);
     output.push(')');
 
-    // Return type
+    // Return type; an inferred one is marked as not declared
     if let Some(ref return_type) = method.return_type {
         output.push(' ');
         output.push_str(return_type);
+    } else if let Some(inferred) = method
+        .inferred_return_type
+        .as_ref()
+        .filter(|_| opts.infer_return_types)
+    {
+        let _ = write!(output, " {inferred}?");
     }
 
     // Classifiers
@@ -113,11 +417,112 @@ const fn render_relationship_symbol(relation_type: RelationType) -> &'static str
     match relation_type {
         RelationType::Inheritance => "--|>",
         RelationType::Implementation => "..|>",
+        RelationType::Nesting => "--",
+        RelationType::Dependency => "..>",
     }
 }
 
+const fn render_relationship_label(relation_type: RelationType) -> Option<&'static str> {
+    match relation_type {
+        RelationType::Nesting => Some("nested"),
+        RelationType::Inheritance | RelationType::Implementation | RelationType::Dependency => None,
+    }
+}
+
+const fn render_composition_symbol(kind: CompositionKind) -> &'static str {
+    match kind {
+        CompositionKind::Composition => "*--",
+        CompositionKind::Aggregation => "o--",
+        CompositionKind::Association => "-->",
+    }
+}
+
+/// The part of a docstring a note shows, with its whitespace collapsed.
+fn docstring_summary(docstring: &str, notes: DocstringNotes) -> Option<String> {
+    let mut lines = docstring
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+    let words: Vec<&str> = match notes {
+        DocstringNotes::Hidden => return None,
+        DocstringNotes::FirstLine => lines.next()?.split_whitespace().collect(),
+        DocstringNotes::FirstParagraph => lines
+            .take_while(|line| !line.is_empty())
+            .flat_map(str::split_whitespace)
+            .collect(),
+    };
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Cut text longer than `max_length` characters at a word boundary, marking the cut with `...`.
+fn truncate_note(text: &str, max_length: usize) -> Cow<'_, str> {
+    if max_length == 0 || text.chars().count() <= max_length {
+        return Cow::Borrowed(text);
+    }
+    let end = text
+        .char_indices()
+        .nth(max_length.saturating_sub(3))
+        .map_or(text.len(), |(idx, _)| idx);
+    let mut kept = &text[..end];
+    if !text[end..].starts_with(' ') {
+        // Drop the partial word, unless it is the only one
+        if let Some((words, _)) = kept.rsplit_once(' ') {
+            kept = words;
+        }
+    }
+    Cow::Owned(format!("{}...", kept.trim_end()))
+}
+
+/// Break text into lines of at most `line_length` characters at word boundaries. Longer words
+/// get a line of their own.
+fn wrap_note(text: &str, line_length: usize) -> Vec<String> {
+    if line_length == 0 {
+        return vec![text.to_owned()];
+    }
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > line_length {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines
+}
+
+/// Render the docstring of a class as a note, e.g. `note for Parser "Parse a file."`.
+///
+/// Lines are joined with Mermaid's `\n` line break, and characters that would end the string
+/// or be read as markup are written as entity codes.
+fn render_note(class: &ClassNode, class_id: &str, opts: &RenderOptions) -> Option<String> {
+    let summary = docstring_summary(class.docstring.as_deref()?, opts.docstrings)?;
+    let summary = truncate_note(&summary, opts.docstring_max_length);
+    let lines: Vec<String> = wrap_note(&summary, opts.docstring_line_length)
+        .iter()
+        .map(|line| line.escape_entities())
+        .collect();
+    Some(format!(
+        "{}note for {class_id} \"{}\"\n",
+        indent(1),
+        lines.join("\\n")
+    ))
+}
+
 #[must_use]
 pub fn render_header(title: Option<&str>, direction: DiagramDirection) -> String {
+    render_header_for("classDiagram", title, direction)
+}
+
+/// The optional title front matter, the diagram keyword and a non-default direction.
+pub(super) fn render_header_for(
+    keyword: &str,
+    title: Option<&str>,
+    direction: DiagramDirection,
+) -> String {
     let mut output = String::new();
 
     if let Some(title) = title {
@@ -128,7 +533,8 @@ pub fn render_header(title: Option<&str>, direction: DiagramDirection) -> String
         output.push_str("---\n");
     }
 
-    output.push_str("classDiagram\n");
+    output.push_str(keyword);
+    output.push('\n');
 
     // Only emit direction if non-default
     if direction != DiagramDirection::default() {
@@ -138,8 +544,99 @@ pub fn render_header(title: Option<&str>, direction: DiagramDirection) -> String
     output
 }
 
+/// Replace characters Mermaid does not accept in class identifiers (e.g. the `.` of a module
+/// path) with `_`.
+pub(super) fn sanitize_mermaid_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Mermaid identifiers of the diagram's classes, keyed by class id.
+///
+/// Classes are drawn under their name unless another class shares it. Those get a sanitized
+/// module-qualified identifier (e.g. `api_settings_Config`) and keep their name as the label.
+/// Names Mermaid does not accept as identifiers, such as the `Outer.Inner` of a nested class,
+/// are sanitized the same way (`Outer_Inner["Outer.Inner"]`).
+pub(super) struct MermaidIds<'a> {
+    index: ClassIndex<'a>,
+    ids: HashMap<&'a str, String>,
+}
+
+impl<'a> MermaidIds<'a> {
+    pub(super) fn new(classes: &[&'a ClassNode], index: ClassIndex<'a>) -> Self {
+        let mut taken: HashSet<String> = classes
+            .iter()
+            .filter(|class| !Self::is_relabeled(&index, class))
+            .map(|class| class.name.clone())
+            .collect();
+
+        let mut ids = HashMap::with_capacity(classes.len());
+        for class in classes {
+            let id = if Self::is_relabeled(&index, class) {
+                let base = if index.is_ambiguous(class) {
+                    sanitize_mermaid_id(&class.id)
+                } else {
+                    sanitize_mermaid_id(&class.name)
+                };
+                let mut id = base.clone();
+                let mut suffix = 2;
+                while taken.contains(&id) {
+                    id = format!("{base}_{suffix}");
+                    suffix += 1;
+                }
+                taken.insert(id.clone());
+                id
+            } else {
+                class.name.clone()
+            };
+            ids.insert(class.id.as_str(), id);
+        }
+
+        Self { index, ids }
+    }
+
+    /// Whether a class is drawn under an identifier other than its name, with its name as the
+    /// label.
+    fn is_relabeled(index: &ClassIndex<'a>, class: &ClassNode) -> bool {
+        index.is_ambiguous(class) || sanitize_mermaid_id(&class.name) != class.name
+    }
+
+    /// The label to show for a class drawn under a qualified or sanitized identifier.
+    pub(super) fn label(&self, class: &'a ClassNode) -> Option<&'a str> {
+        Self::is_relabeled(&self.index, class).then_some(class.name.as_str())
+    }
+
+    /// The identifier of the class with the given id, or the id itself if it is not in the diagram.
+    pub(super) fn class_id(&self, id: &str) -> String {
+        self.ids.get(id).cloned().unwrap_or_else(|| id.to_owned())
+    }
+
+    /// The identifier of the class an edge endpoint resolves to, or its written name.
+    pub(super) fn ref_id(&self, target: &ClassRef) -> String {
+        self.index
+            .resolve(target)
+            .map_or_else(|| target.name.clone(), |class| self.class_id(&class.id))
+    }
+}
+
 #[must_use]
 pub fn render_class(class: &ClassNode, opts: &RenderOptions) -> String {
+    render_class_as(class, &class.name, None, opts)
+}
+
+fn render_class_as(
+    class: &ClassNode,
+    class_id: &str,
+    label: Option<&str>,
+    opts: &RenderOptions,
+) -> String {
     let mut output = String::new();
     let outer_indent = indent(1);
     let inner_indent = indent(2);
@@ -147,7 +644,13 @@ pub fn render_class(class: &ClassNode, opts: &RenderOptions) -> String {
     // Class declaration
     output.push_str(&outer_indent);
     output.push_str("class ");
-    output.push_str(&class.name);
+    output.push_str(class_id);
+
+    if let Some(label) = label {
+        output.push_str("[\"");
+        output.push_str(&label.escape_entities());
+        output.push_str("\"]");
+    }
 
     // Type parameters (generics)
     if let Some(ref type_params) = class.type_params {
@@ -162,20 +665,37 @@ pub fn render_class(class: &ClassNode, opts: &RenderOptions) -> String {
         // Class type annotation
         render_class_annotation(&mut output, &inner_indent, class.class_type);
 
+        // Enum members, ahead of any other attributes
+        for attr in &class.attributes {
+            if attr.is_enum_member && opts.shows(attr.visibility) {
+                render_enum_member(&mut output, &inner_indent, attr, opts.show_enum_values);
+            }
+        }
+
         // Attributes
         for attr in &class.attributes {
-            if opts.hide_private_members && attr.visibility == Visibility::Private {
+            if attr.is_enum_member || !opts.shows(attr.visibility) {
                 continue;
             }
             render_attribute(&mut output, &inner_indent, attr);
         }
 
         // Methods
-        for method in &class.methods {
-            if opts.hide_private_members && method.visibility == Visibility::Private {
+        for method in arrange_overloads(&class.methods, opts.overload_style) {
+            if !opts.shows(method.visibility) {
                 continue;
             }
-            render_method(&mut output, &inner_indent, method);
+            // Listed overloads differ only in their types, so those are always shown
+            let method_opts =
+                if opts.overload_style == OverloadStyle::Expand && method.is_overload() {
+                    RenderOptions {
+                        parameter_style: ParameterStyle::Full,
+                        ..*opts
+                    }
+                } else {
+                    *opts
+                };
+            render_method(&mut output, &inner_indent, &method, &method_opts);
         }
 
         output.push_str(&outer_indent);
@@ -190,23 +710,135 @@ pub fn render_class(class: &ClassNode, opts: &RenderOptions) -> String {
 pub fn render_relationship(relationship: &RelationshipEdge) -> String {
     let symbol = render_relationship_symbol(relationship.relation_type);
 
-    format!(
-        "{}{} {} {}\n",
+    let mut output = format!(
+        "{}{} {} {}",
         indent(1),
         relationship.from,
         symbol,
-        relationship.to
-    )
+        relationship.to.name
+    );
+    if let Some(label) = render_relationship_label(relationship.relation_type) {
+        output.push_str(" : ");
+        output.push_str(label);
+    }
+    output.push('\n');
+    output
 }
 
 #[must_use]
 pub fn render_composition(composition: &CompositionEdge) -> String {
-    format!(
-        "{}{} *-- {}\n",
-        indent(1),
-        composition.container,
-        composition.contained
-    )
+    let mut output = match composition.inverse_cardinality {
+        // Both sides of a bidirectional association, e.g. `Parent "1" <--> "*" Child`
+        Some(inverse) => format!(
+            "{}{} \"{}\" <--> \"{}\" {}",
+            indent(1),
+            composition.container,
+            inverse.as_str(),
+            composition.cardinality.as_str(),
+            composition.contained.name
+        ),
+        None => format!(
+            "{}{} {} \"{}\" {}",
+            indent(1),
+            composition.container,
+            render_composition_symbol(composition.kind),
+            composition.cardinality.as_str(),
+            composition.contained.name
+        ),
+    };
+    let mut label = composition.fields.join(", ");
+    if let (Some(_), Some(back_populates)) =
+        (composition.inverse_cardinality, &composition.back_populates)
+    {
+        let _ = write!(label, " / {back_populates}");
+    }
+    if let Some(ref qualifier) = composition.qualifier {
+        if !label.is_empty() {
+            label.push(' ');
+        }
+        let _ = write!(label, "[{qualifier}]");
+    }
+    if !label.is_empty() {
+        output.push_str(" : ");
+        output.push_str(&label);
+    }
+    output.push('\n');
+    output
+}
+
+/// Pair the two sides of an association declared with `back_populates` (`Parent.children`
+/// and `Child.parent`) into one bidirectional edge carrying both cardinalities.
+fn pair_inverse_associations(compositions: IndexSet<CompositionEdge>) -> IndexSet<CompositionEdge> {
+    let edges: Vec<CompositionEdge> = compositions.into_iter().collect();
+    let mut paired = vec![false; edges.len()];
+    let mut output = IndexSet::new();
+
+    for (idx, edge) in edges.iter().enumerate() {
+        if paired[idx] {
+            continue;
+        }
+        let mut edge = edge.clone();
+        if let (Some(back_populates), [field]) = (&edge.back_populates, edge.fields.as_slice()) {
+            let inverse = (idx + 1..edges.len()).find(|&other| {
+                let other_edge = &edges[other];
+                !paired[other]
+                    && other_edge.container == edge.contained.name
+                    && other_edge.contained.name == edge.container
+                    && other_edge.fields.as_slice() == std::slice::from_ref(back_populates)
+                    && other_edge.back_populates.as_ref() == Some(field)
+            });
+            if let Some(other) = inverse {
+                paired[other] = true;
+                edge.inverse_cardinality = Some(edges[other].cardinality);
+            }
+        }
+        output.insert(edge);
+    }
+    output
+}
+
+/// Merge edges that differ only in the field that creates them into one edge whose label
+/// lists all the fields (e.g. `Order *-- "1" Address : primary, billing`).
+fn merge_parallel_compositions(
+    compositions: IndexSet<CompositionEdge>,
+) -> IndexSet<CompositionEdge> {
+    let mut merged: IndexMap<CompositionEdge, Vec<String>> = IndexMap::new();
+    for mut comp in compositions {
+        let fields = std::mem::take(&mut comp.fields);
+        merged.entry(comp).or_default().extend(fields);
+    }
+    merged
+        .into_iter()
+        .map(|(comp, fields)| CompositionEdge { fields, ..comp })
+        .collect()
+}
+
+/// The diagram's attribute edges between Mermaid identifiers, deduplicated in a stable order,
+/// with `back_populates` pairs joined and parallel edges merged if requested.
+pub(super) fn diagram_compositions(
+    diagram: &Diagram,
+    ids: &MermaidIds,
+    opts: &RenderOptions,
+) -> IndexSet<CompositionEdge> {
+    let mut unique_compositions: IndexSet<_> = diagram
+        .compositions
+        .iter()
+        .map(|comp| CompositionEdge {
+            container: ids.class_id(&comp.container),
+            contained: ids.ref_id(&comp.contained).into(),
+            kind: comp.kind,
+            cardinality: comp.cardinality,
+            qualifier: comp.qualifier.clone(),
+            fields: comp.fields.clone(),
+            back_populates: comp.back_populates.clone(),
+            inverse_cardinality: comp.inverse_cardinality,
+        })
+        .collect();
+    unique_compositions = pair_inverse_associations(unique_compositions);
+    if opts.merge_parallel_edges {
+        unique_compositions = merge_parallel_compositions(unique_compositions);
+    }
+    unique_compositions
 }
 
 /// Render a full Mermaid class diagram.
@@ -223,12 +855,61 @@ pub fn render_diagram(
     let mut output = String::with_capacity(1024);
     output.push_str(&render_header(title, opts.direction));
 
-    for class in diagram.classes_topologically_sorted_unique() {
-        output.push_str(&render_class(class, opts));
+    let classes = diagram.classes_topologically_sorted_unique();
+    let ids = MermaidIds::new(&classes, diagram.class_index());
+    for &class in &classes {
+        output.push_str(&render_class_as(
+            class,
+            &ids.class_id(&class.id),
+            ids.label(class),
+            opts,
+        ));
     }
 
+    // Class docstrings as notes, after the classes they belong to
+    let notes: Vec<String> = classes
+        .iter()
+        .filter_map(|class| render_note(class, &ids.class_id(&class.id), opts))
+        .collect();
+    if !notes.is_empty() {
+        output.push_str(&notes.concat());
+        output.push('\n');
+    }
+
+    // Compositions (deduped; stable order)
+    let unique_compositions = diagram_compositions(diagram, &ids, opts);
+
     // Relationships (deduped; stable order)
-    let unique_relationships: IndexSet<_> = diagram.relationships.iter().collect();
+    let mut unique_relationships: IndexSet<_> = diagram
+        .relationships
+        .iter()
+        .map(|rel| RelationshipEdge {
+            from: ids.class_id(&rel.from),
+            to: ids.ref_id(&rel.to).into(),
+            relation_type: rel.relation_type,
+        })
+        .collect();
+
+    // Dependencies are only drawn between classes that are not already related more strongly
+    let related: HashSet<(String, String)> = unique_relationships
+        .iter()
+        .filter(|rel| rel.relation_type != RelationType::Dependency)
+        .map(|rel| (rel.from.clone(), rel.to.name.clone()))
+        .chain(unique_compositions.iter().flat_map(|comp| {
+            let edge = (comp.container.clone(), comp.contained.name.clone());
+            // A bidirectional association relates the classes both ways
+            let inverse = comp
+                .inverse_cardinality
+                .map(|_| (edge.1.clone(), edge.0.clone()));
+            std::iter::once(edge).chain(inverse)
+        }))
+        .collect();
+    unique_relationships.retain(|rel| {
+        rel.relation_type != RelationType::Dependency
+            || (rel.from != rel.to.name
+                && !related.contains(&(rel.from.clone(), rel.to.name.clone())))
+    });
+
     if !unique_relationships.is_empty() {
         for (idx, rel) in unique_relationships.iter().enumerate() {
             output.push_str(&render_relationship(rel));
@@ -238,8 +919,6 @@ pub fn render_diagram(
         }
     }
 
-    // Compositions (deduped; stable order)
-    let unique_compositions: IndexSet<_> = diagram.compositions.iter().collect();
     if !unique_compositions.is_empty() {
         if !unique_relationships.is_empty() {
             output.push('\n');
@@ -262,28 +941,35 @@ pub fn render_diagram(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render::renderer::Cardinality;
 
     #[test]
     fn test_render_simple_class() {
         let class = ClassNode {
+            id: "Person".to_string(),
             name: "Person".to_string(),
             type_params: None,
             class_type: ClassType::Regular,
-            attributes: vec![Attribute {
-                name: "name".to_string(),
-                type_annotation: "str".to_string(),
-                visibility: Visibility::Public,
-            }],
+            attributes: vec![Attribute::new("name", "str", Visibility::Public)],
             methods: vec![MethodSignature {
                 name: "greet".to_string(),
-                parameters: "self".to_string(),
+                parameters: vec![MethodParameter {
+                    name: "self".to_string(),
+                    kind: ParameterKind::PositionalOrKeyword,
+                    type_annotation: None,
+                    default: None,
+                }],
                 return_type: Some("str".to_string()),
+                inferred_return_type: None,
                 visibility: Visibility::Public,
                 is_static: false,
                 is_abstract: false,
                 is_async: false,
                 decorators: vec![],
             }],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
         };
 
         let output = render_class(&class, &RenderOptions::default());
@@ -292,11 +978,295 @@ mod tests {
         assert!(output.contains("+ greet(self) str"));
     }
 
+    #[test]
+    fn test_render_attribute_default_and_modifiers() {
+        let tags = Attribute::new("tags", "dict[str, int]", Visibility::Public);
+        let render = |attr: &Attribute| {
+            let mut output = String::new();
+            render_attribute(&mut output, "", attr);
+            output
+        };
+
+        assert_eq!(render(&tags), "+ dict[str, int] tags\n");
+        assert_eq!(
+            render(&Attribute {
+                default: Some("dict()".to_string()),
+                modifiers: vec!["alias='labels'".to_string(), "frozen".to_string()],
+                ..tags.clone()
+            }),
+            "+ dict[str, int] tags = dict() [alias='labels', frozen]\n"
+        );
+        assert_eq!(
+            render(&Attribute {
+                default: Some("{}".to_string()),
+                ..tags
+            }),
+            "+ dict[str, int] tags = ...\n"
+        );
+    }
+
+    #[test]
+    fn test_render_attribute_qualifiers() {
+        let limit = Attribute {
+            default: Some("10".to_string()),
+            modifiers: vec!["kw_only".to_string()],
+            ..Attribute::new("limit", "int", Visibility::Public)
+        };
+        let render = |attr: &Attribute| {
+            let mut output = String::new();
+            render_attribute(&mut output, "", attr);
+            output
+        };
+
+        assert_eq!(
+            render(&Attribute {
+                is_static: true,
+                ..limit.clone()
+            }),
+            "+ int limit = 10 [kw_only]$\n"
+        );
+        assert_eq!(
+            render(&Attribute {
+                is_static: true,
+                is_final: true,
+                ..limit.clone()
+            }),
+            "+ int limit = 10 [final, kw_only]$\n"
+        );
+        assert_eq!(
+            render(&Attribute {
+                is_init_var: true,
+                ..limit
+            }),
+            "+ int limit = 10 [init_only, kw_only]\n"
+        );
+    }
+
+    fn move_method() -> MethodSignature {
+        let parameter =
+            |name: &str, type_annotation: Option<&str>, default: Option<&str>| MethodParameter {
+                name: name.to_string(),
+                kind: ParameterKind::PositionalOrKeyword,
+                type_annotation: type_annotation.map(str::to_string),
+                default: default.map(str::to_string),
+            };
+        MethodSignature {
+            name: "move".to_string(),
+            parameters: vec![
+                parameter("self", None, None),
+                parameter("dx", Some("int"), None),
+                parameter("dy", Some("int"), Some("0")),
+                parameter("label", None, Some("'x'")),
+            ],
+            return_type: None,
+            inferred_return_type: None,
+            visibility: Visibility::Public,
+            is_static: false,
+            is_abstract: false,
+            is_async: false,
+            decorators: vec![],
+        }
+    }
+
+    #[test]
+    fn test_render_parameter_styles() {
+        let method = move_method();
+        let render = |opts: RenderOptions| {
+            let mut output = String::new();
+            render_parameters(&mut output, &method, &opts);
+            output
+        };
+
+        assert_eq!(render(RenderOptions::default()), "self, dx, dy, label");
+        assert_eq!(
+            render(RenderOptions {
+                parameter_style: ParameterStyle::Types,
+                ..RenderOptions::default()
+            }),
+            "self, dx: int, dy: int, label"
+        );
+        assert_eq!(
+            render(RenderOptions {
+                parameter_style: ParameterStyle::Full,
+                hide_self: true,
+                ..RenderOptions::default()
+            }),
+            "dx: int, dy: int = 0, label='x'"
+        );
+    }
+
+    #[test]
+    fn test_render_note() {
+        let docstring = "
+            Parse a \"config\" file.
+
+            Reads <sections> and # comments.
+        ";
+        assert_eq!(
+            docstring_summary(docstring, DocstringNotes::FirstLine).as_deref(),
+            Some("Parse a \"config\" file.")
+        );
+        assert_eq!(
+            docstring_summary(
+                "Parse a\n    config file.\n\n    More.",
+                DocstringNotes::FirstParagraph
+            )
+            .as_deref(),
+            Some("Parse a config file.")
+        );
+        assert_eq!(docstring_summary(docstring, DocstringNotes::Hidden), None);
+        assert_eq!(docstring_summary("  \n  ", DocstringNotes::FirstLine), None);
+
+        assert_eq!(
+            truncate_note("Parse a config file.", 0),
+            "Parse a config file."
+        );
+        assert_eq!(
+            truncate_note("Parse a config file.", 20),
+            "Parse a config file."
+        );
+        assert_eq!(truncate_note("Parse a config file.", 14), "Parse a...");
+        assert_eq!(truncate_note("Parse a config file.", 11), "Parse a...");
+        assert_eq!(truncate_note("Unbreakable", 8), "Unbre...");
+
+        assert_eq!(
+            wrap_note("Parse a config file.", 10),
+            vec!["Parse a", "config", "file."]
+        );
+        assert_eq!(
+            wrap_note("Parse a config file.", 0),
+            vec!["Parse a config file."]
+        );
+        assert_eq!(wrap_note("Configuration", 5), vec!["Configuration"]);
+
+        let class = ClassNode {
+            id: "Parser".to_string(),
+            name: "Parser".to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes: vec![],
+            methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: Some(docstring.to_string()),
+        };
+        let opts = RenderOptions {
+            docstrings: DocstringNotes::FirstParagraph,
+            docstring_line_length: 20,
+            ..RenderOptions::default()
+        };
+        assert_eq!(
+            render_note(&class, "Parser", &RenderOptions::default()),
+            None
+        );
+        assert_eq!(
+            render_note(&class, "Parser", &opts).unwrap(),
+            "    note for Parser \"Parse a #quot;config#quot;\\nfile.\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_diagram_disambiguates_duplicate_names() {
+        let class = |id: &str| ClassNode {
+            id: id.to_string(),
+            name: id.rsplit('.').next().unwrap().to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes: vec![],
+            methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
+        };
+
+        let mut diagram = Diagram::new();
+        diagram.add_class(class("api.settings.Config"));
+        diagram.add_class(class("db.settings.Config"));
+        diagram.add_class(class("app.App"));
+        diagram.add_composition(CompositionEdge {
+            container: "app.App".to_string(),
+            contained: ClassRef {
+                id: Some("db.settings.Config".to_string()),
+                name: "Config".to_string(),
+            },
+            kind: CompositionKind::Composition,
+            cardinality: Cardinality::One,
+            qualifier: None,
+            fields: vec!["db".to_string()],
+            back_populates: None,
+            inverse_cardinality: None,
+        });
+
+        let output = render_diagram(&diagram, None, &RenderOptions::default()).unwrap();
+        assert!(output.contains("class api_settings_Config[\"Config\"]\n"));
+        assert!(output.contains("class db_settings_Config[\"Config\"]\n"));
+        assert!(output.contains("class App\n"));
+        assert!(output.contains("App *-- \"1\" db_settings_Config : db"));
+    }
+
+    #[test]
+    fn test_render_diagram_labels_generic_duplicates() {
+        let class = |id: &str, type_params: &str| ClassNode {
+            id: id.to_string(),
+            name: id.rsplit('.').next().unwrap().to_string(),
+            type_params: Some(type_params.to_string()),
+            class_type: ClassType::Regular,
+            attributes: vec![],
+            methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
+        };
+
+        let mut diagram = Diagram::new();
+        diagram.add_class(class("api.Box", "T"));
+        diagram.add_class(class("db.Box", "K, V"));
+
+        let output = render_diagram(&diagram, None, &RenderOptions::default()).unwrap();
+        assert!(output.contains("class api_Box[\"Box\"] ~T~\n"));
+        assert!(output.contains("class db_Box[\"Box\"] ~K, V~\n"));
+    }
+
+    #[test]
+    fn test_render_composition_kinds() {
+        let edge = |kind, cardinality, qualifier: Option<&str>| CompositionEdge {
+            container: "Registry".to_string(),
+            contained: "Plugin".into(),
+            kind,
+            cardinality,
+            qualifier: qualifier.map(str::to_string),
+            fields: vec!["plugins".to_string()],
+            back_populates: None,
+            inverse_cardinality: None,
+        };
+
+        assert_eq!(
+            render_composition(&edge(CompositionKind::Composition, Cardinality::One, None)),
+            "    Registry *-- \"1\" Plugin : plugins\n"
+        );
+        assert_eq!(
+            render_composition(&edge(
+                CompositionKind::Aggregation,
+                Cardinality::ZeroOrOne,
+                None
+            )),
+            "    Registry o-- \"0..1\" Plugin : plugins\n"
+        );
+        assert_eq!(
+            render_composition(&edge(
+                CompositionKind::Association,
+                Cardinality::Many,
+                Some("str")
+            )),
+            "    Registry --> \"*\" Plugin : plugins [str]\n"
+        );
+    }
+
     #[test]
     fn test_render_relationship() {
         let rel = RelationshipEdge {
             from: "Dog".to_string(),
-            to: "Animal".to_string(),
+            to: "Animal".into(),
             relation_type: RelationType::Inheritance,
         };
 
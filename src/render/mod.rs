@@ -1,3 +1,4 @@
+pub mod er_renderer;
 pub mod mermaid_escape;
 pub mod mermaid_renderer;
 pub mod output_format;
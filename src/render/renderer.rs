@@ -3,25 +3,171 @@
 pub enum Visibility {
     Public,
     Private,
-    /// Reserved for future use - Python doesn't have true protected visibility
-    #[allow(dead_code)]
+    /// A single-underscore name under [`VisibilityPolicy::Mangling`]; Python has no true
+    /// protected visibility, only the convention
     Protected,
 }
 
+impl Visibility {
+    const fn rank(self) -> u8 {
+        match self {
+            Self::Private => 0,
+            Self::Protected => 1,
+            Self::Public => 2,
+        }
+    }
+
+    /// Whether a member with this visibility is at least as visible as `minimum`.
+    #[must_use]
+    pub const fn is_at_least(self, minimum: Self) -> bool {
+        self.rank() >= minimum.rank()
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public => write!(f, "public"),
+            Self::Protected => write!(f, "protected"),
+            Self::Private => write!(f, "private"),
+        }
+    }
+}
+
+impl std::str::FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "public" => Ok(Self::Public),
+            "protected" => Ok(Self::Protected),
+            "private" => Ok(Self::Private),
+            _ => Err(format!(
+                "invalid visibility: {s} (expected public, protected, or private)"
+            )),
+        }
+    }
+}
+
+/// How the leading underscores of a member name map to its visibility.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VisibilityPolicy {
+    /// Any leading underscore makes a member private, e.g. `_cache` and `__token` (default)
+    #[default]
+    Underscore,
+    /// `_cache` is protected and only name-mangled members like `__token` are private
+    Mangling,
+}
+
+impl VisibilityPolicy {
+    /// The visibility of a member name; dunder names like `__init__` are public.
+    #[must_use]
+    pub fn visibility_for_name(self, name: &str) -> Visibility {
+        let is_dunder = name.starts_with("__") && name.ends_with("__");
+        if is_dunder || !name.starts_with('_') {
+            Visibility::Public
+        } else if self == Self::Mangling && !name.starts_with("__") {
+            Visibility::Protected
+        } else {
+            Visibility::Private
+        }
+    }
+}
+
+impl std::fmt::Display for VisibilityPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Underscore => write!(f, "underscore"),
+            Self::Mangling => write!(f, "mangling"),
+        }
+    }
+}
+
+impl std::str::FromStr for VisibilityPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "underscore" => Ok(Self::Underscore),
+            "mangling" => Ok(Self::Mangling),
+            _ => Err(format!(
+                "invalid visibility policy: {s} (expected underscore or mangling)"
+            )),
+        }
+    }
+}
+
 /// Represents a class attribute/field
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Attribute {
     pub name: String,
     pub type_annotation: String,
     pub visibility: Visibility,
+    /// Default value of a model field, e.g. `0`, or `list()` for `default_factory=list`
+    pub default: Option<String>,
+    /// Field options worth showing, e.g. `alias='userName'`, `frozen` or `kw_only`
+    pub modifiers: Vec<String>,
+    /// Declared as `ClassVar[...]`, shared by all instances
+    pub is_static: bool,
+    /// Declared as `Final[...]`, so it cannot be reassigned
+    pub is_final: bool,
+    /// Declared as a dataclass `InitVar[...]`, passed to `__init__` but not stored
+    pub is_init_var: bool,
+    /// A member of an enum class, e.g. `RED = 1`, whose value is kept as the default
+    pub is_enum_member: bool,
+}
+
+impl Attribute {
+    /// A plain attribute with no default, modifiers or qualifiers.
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        type_annotation: impl Into<String>,
+        visibility: Visibility,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            type_annotation: type_annotation.into(),
+            visibility,
+            default: None,
+            modifiers: vec![],
+            is_static: false,
+            is_final: false,
+            is_init_var: false,
+            is_enum_member: false,
+        }
+    }
+}
+
+/// Kind of a method parameter, which determines its prefix and the `/` and `*` separators.
+/// Kinds are ordered as they appear in a signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParameterKind {
+    PositionalOnly,
+    PositionalOrKeyword,
+    VarPositional, // *args
+    KeywordOnly,
+    VarKeyword, // **kwargs
 }
 
 /// Represents a method parameter
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodParameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    pub type_annotation: Option<String>,
+    pub default: Option<String>,
+}
+
+/// Represents a method signature
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MethodSignature {
     pub name: String,
-    pub parameters: String,
+    pub parameters: Vec<MethodParameter>,
     pub return_type: Option<String>,
+    /// Return type inferred from the body of an unannotated method, e.g. `None` for a method
+    /// without a `return`
+    pub inferred_return_type: Option<String>,
     pub visibility: Visibility,
     pub is_static: bool,
     pub is_abstract: bool,
@@ -29,25 +175,181 @@ pub struct MethodSignature {
     pub decorators: Vec<String>,
 }
 
-/// Type of class (regular, abstract, interface/protocol, enum, dataclass)
+impl MethodSignature {
+    /// Whether this is an `@overload` variant rather than the method's implementation.
+    #[must_use]
+    pub fn is_overload(&self) -> bool {
+        self.decorators
+            .iter()
+            .any(|decorator| decorator == "@overload")
+    }
+}
+
+/// Type of class (regular, abstract, interface/protocol, enum, dataclass, ...)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClassType {
     Regular,
     Abstract,
     Interface,
+    /// `enum.Enum` and enums with other bases
     Enumeration,
+    /// `enum.IntEnum`
+    IntEnum,
+    /// `enum.StrEnum`
+    StrEnum,
+    /// `enum.Flag` and `enum.IntFlag`
+    Flag,
     Dataclass,
     Final,
+    NamedTuple,
+    TypedDict,
+    NewType,
+    PydanticModel,
+    AttrsClass,
+    MsgspecStruct,
+    DjangoModel,
+    SqlAlchemyModel,
+}
+
+impl ClassType {
+    /// Whether the class declares its fields as annotated class attributes, with defaults and
+    /// field options (dataclasses, `dataclass_transform`, Pydantic, attrs and msgspec).
+    #[must_use]
+    pub const fn is_model(self) -> bool {
+        matches!(
+            self,
+            Self::Dataclass | Self::PydanticModel | Self::AttrsClass | Self::MsgspecStruct
+        )
+    }
+
+    /// Whether the class is an enum of any kind.
+    #[must_use]
+    pub const fn is_enum(self) -> bool {
+        matches!(
+            self,
+            Self::Enumeration | Self::IntEnum | Self::StrEnum | Self::Flag
+        )
+    }
+
+    /// Whether subclasses share the class type without being declared the same way again
+    /// (e.g. subclasses of a Pydantic model are Pydantic models).
+    #[must_use]
+    pub const fn is_inherited(self) -> bool {
+        matches!(
+            self,
+            Self::PydanticModel | Self::MsgspecStruct | Self::DjangoModel | Self::SqlAlchemyModel
+        )
+    }
 }
 
 /// Represents a class node in the diagram
 #[derive(Debug, Clone)]
 pub struct ClassNode {
+    /// Project-qualified identity, e.g. `pkg.models.User` (just the name when the module is unknown)
+    pub id: String,
+    /// Name shown in the diagram, e.g. `User` or `Outer.Inner` for nested classes
     pub name: String,
     pub type_params: Option<String>,
     pub class_type: ClassType,
     pub attributes: Vec<Attribute>,
     pub methods: Vec<MethodSignature>,
+    /// Defined inside a module-level `if`/`try`/`with` block, so other branches may define it too
+    pub is_conditional: bool,
+    /// Defined in a `.pyi` stub file, whose members take precedence over the implementation's
+    pub is_stub: bool,
+    /// The class docstring as written, shown as a note with `--docstrings`
+    pub docstring: Option<String>,
+}
+
+impl ClassNode {
+    /// Merge another definition of the same class (e.g. from an `else` branch) into this one,
+    /// keeping the first definition's members and appending any new ones. A method defined in
+    /// both is taken from the first definition only, with all of its overloads.
+    pub fn merge_definition(&mut self, other: Self) {
+        for attr in other.attributes {
+            if !self
+                .attributes
+                .iter()
+                .any(|existing| existing.name == attr.name)
+            {
+                self.attributes.push(attr);
+            }
+        }
+        let defined: std::collections::HashSet<String> = self
+            .methods
+            .iter()
+            .map(|method| method.name.clone())
+            .collect();
+        self.methods.extend(
+            other
+                .methods
+                .into_iter()
+                .filter(|method| !defined.contains(&method.name)),
+        );
+        if self.type_params.is_none() {
+            self.type_params = other.type_params;
+        }
+        if self.class_type == ClassType::Regular {
+            self.class_type = other.class_type;
+        }
+        if self.docstring.is_none() {
+            self.docstring = other.docstring;
+        }
+    }
+
+    /// Merge the stub (`.pyi`) definition of this class into its implementation. The stub's
+    /// typed members replace the implementation's members of the same name (all overloads of a
+    /// method at once), and members found only in the implementation are kept.
+    pub fn merge_stub(&mut self, stub: Self) {
+        for attr in stub.attributes {
+            match self
+                .attributes
+                .iter_mut()
+                .find(|existing| existing.name == attr.name)
+            {
+                Some(existing) => *existing = attr,
+                None => self.attributes.push(attr),
+            }
+        }
+
+        let implemented: std::collections::HashSet<String> = self
+            .methods
+            .iter()
+            .map(|method| method.name.clone())
+            .collect();
+        let mut methods = Vec::with_capacity(self.methods.len());
+        for method in std::mem::take(&mut self.methods) {
+            if !stub.methods.iter().any(|typed| typed.name == method.name) {
+                methods.push(method);
+            } else if !methods
+                .iter()
+                .any(|merged: &MethodSignature| merged.name == method.name)
+            {
+                methods.extend(
+                    stub.methods
+                        .iter()
+                        .filter(|typed| typed.name == method.name)
+                        .cloned(),
+                );
+            }
+        }
+        methods.extend(
+            stub.methods
+                .into_iter()
+                .filter(|typed| !implemented.contains(&typed.name)),
+        );
+        self.methods = methods;
+
+        if stub.type_params.is_some() {
+            self.type_params = stub.type_params;
+        }
+        if stub.class_type != ClassType::Regular {
+            self.class_type = stub.class_type;
+        }
+        if self.docstring.is_none() {
+            self.docstring = stub.docstring;
+        }
+    }
 }
 
 /// Type of relationship between classes
@@ -55,21 +357,89 @@ pub struct ClassNode {
 pub enum RelationType {
     Inheritance,    // Solid line: --|>
     Implementation, // Dotted line: ..|> (for interfaces/abstracts)
+    Nesting,        // Solid link: -- (nested class to its enclosing class)
+    Dependency,     // Dotted arrow: ..> (types used in method signatures)
+}
+
+/// A reference to a class from an edge, which may or may not be defined in the diagram
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassRef {
+    /// Qualified identity the reference resolved to through the imports, if any
+    pub id: Option<String>,
+    /// Name to draw when the reference does not match any class in the diagram
+    pub name: String,
+}
+
+impl From<&str> for ClassRef {
+    fn from(name: &str) -> Self {
+        Self {
+            id: None,
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl From<String> for ClassRef {
+    fn from(name: String) -> Self {
+        Self { id: None, name }
+    }
 }
 
-/// Represents an inheritance or implementation relationship
+/// Represents an inheritance, implementation, nesting or dependency relationship
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RelationshipEdge {
+    /// Id of the source class
     pub from: String,
-    pub to: String,
+    pub to: ClassRef,
     pub relation_type: RelationType,
 }
 
-/// Represents a composition relationship
+/// How a class holds the classes its attributes refer to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CompositionKind {
+    #[default]
+    Composition, // Solid diamond: *-- (a plain `X` attribute)
+    Aggregation, // Hollow diamond: o-- (`Optional[X]`, `list[X]`, ...)
+    Association, // Arrow: --> (`dict[K, X]`, qualified by the key type)
+}
+
+/// Number of instances on the contained side of a composition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Cardinality {
+    #[default]
+    One, // "1"
+    ZeroOrOne, // "0..1"
+    Many,      // "*"
+}
+
+impl Cardinality {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::One => "1",
+            Self::ZeroOrOne => "0..1",
+            Self::Many => "*",
+        }
+    }
+}
+
+/// Represents a composition, aggregation or association relationship
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CompositionEdge {
+    /// Id of the containing class
     pub container: String,
-    pub contained: String,
+    pub contained: ClassRef,
+    pub kind: CompositionKind,
+    pub cardinality: Cardinality,
+    /// Key type of a mapping association (e.g. `str` for `dict[str, X]`)
+    pub qualifier: Option<String>,
+    /// Names of the attributes that create the relationship, drawn as the edge label
+    pub fields: Vec<String>,
+    /// Attribute of the contained class that holds the reverse side (SQLAlchemy
+    /// `back_populates`)
+    pub back_populates: Option<String>,
+    /// Cardinality of the reverse side, once both sides are drawn as one bidirectional edge
+    pub inverse_cardinality: Option<Cardinality>,
 }
 
 /// Class diagram direction.
@@ -113,6 +483,161 @@ impl std::str::FromStr for DiagramDirection {
     }
 }
 
+/// How much of each method parameter to render.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParameterStyle {
+    /// Parameter names only, e.g. `move(self, dx, dy)` (default)
+    #[default]
+    Names,
+    /// Names and type annotations, e.g. `move(self, dx: int, dy: int)`
+    Types,
+    /// Names, type annotations and defaults, e.g. `move(self, dx: int, dy: int = 0)`
+    Full,
+}
+
+impl std::fmt::Display for ParameterStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Names => write!(f, "names"),
+            Self::Types => write!(f, "types"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl std::str::FromStr for ParameterStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "names" => Ok(Self::Names),
+            "types" => Ok(Self::Types),
+            "full" => Ok(Self::Full),
+            _ => Err(format!(
+                "invalid parameter style: {s} (expected names, types, or full)"
+            )),
+        }
+    }
+}
+
+/// How the `@overload` variants of a method are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverloadStyle {
+    /// One entry per overloaded method, with the union of the overloads' parameter and
+    /// return types in place of the implementation (default)
+    #[default]
+    Collapse,
+    /// Each overload with its full typed signature, hiding the implementation
+    Expand,
+}
+
+impl std::fmt::Display for OverloadStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Collapse => write!(f, "collapse"),
+            Self::Expand => write!(f, "expand"),
+        }
+    }
+}
+
+impl std::str::FromStr for OverloadStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "collapse" => Ok(Self::Collapse),
+            "expand" => Ok(Self::Expand),
+            _ => Err(format!(
+                "invalid overload style: {s} (expected collapse or expand)"
+            )),
+        }
+    }
+}
+
+/// How much of each class docstring is attached to the diagram as a note.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocstringNotes {
+    /// No notes (default)
+    #[default]
+    Hidden,
+    /// The first line of the docstring, usually its summary
+    FirstLine,
+    /// The first paragraph, up to the first blank line
+    FirstParagraph,
+}
+
+impl std::fmt::Display for DocstringNotes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hidden => write!(f, "none"),
+            Self::FirstLine => write!(f, "line"),
+            Self::FirstParagraph => write!(f, "paragraph"),
+        }
+    }
+}
+
+impl std::str::FromStr for DocstringNotes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::Hidden),
+            "line" => Ok(Self::FirstLine),
+            "paragraph" => Ok(Self::FirstParagraph),
+            _ => Err(format!(
+                "invalid docstring notes: {s} (expected none, line or paragraph)"
+            )),
+        }
+    }
+}
+
+/// Kind of Mermaid diagram to render.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagramKind {
+    /// UML class diagram (`classDiagram`, default)
+    #[default]
+    Class,
+    /// Entity-relationship diagram (`erDiagram`) of the classes' attributes and associations
+    Er,
+}
+
+impl std::fmt::Display for DiagramKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Class => write!(f, "class"),
+            Self::Er => write!(f, "er"),
+        }
+    }
+}
+
+impl std::str::FromStr for DiagramKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "class" => Ok(Self::Class),
+            "er" => Ok(Self::Er),
+            _ => Err(format!("invalid diagram kind: {s} (expected class or er)")),
+        }
+    }
+}
+
+impl Attribute {
+    /// Whether the attribute is marked as the primary key of its table.
+    #[must_use]
+    pub fn is_primary_key(&self) -> bool {
+        self.modifiers.iter().any(|modifier| modifier == "PK")
+    }
+
+    /// The column referenced by a foreign key attribute, e.g. `parents.id` for `FK parents.id`.
+    #[must_use]
+    pub fn foreign_key(&self) -> Option<&str> {
+        self.modifiers
+            .iter()
+            .find_map(|modifier| modifier.strip_prefix("FK "))
+    }
+}
+
 /// The complete diagram structure
 #[derive(Debug, Clone, Default)]
 pub struct Diagram {
@@ -120,6 +645,7 @@ pub struct Diagram {
     pub relationships: Vec<RelationshipEdge>,
     pub compositions: Vec<CompositionEdge>,
     abstract_or_interface_index: std::collections::HashMap<String, bool>,
+    inherited_type_index: std::collections::HashMap<String, ClassType>,
 }
 
 impl Diagram {
@@ -136,11 +662,43 @@ impl Diagram {
     pub fn add_class(&mut self, class: ClassNode) {
         let is_abstract_or_interface =
             matches!(class.class_type, ClassType::Abstract | ClassType::Interface);
-        let entry = self
-            .abstract_or_interface_index
-            .entry(class.name.clone())
-            .or_insert(false);
-        *entry = *entry || is_abstract_or_interface;
+        for key in [&class.name, &class.id] {
+            let entry = self
+                .abstract_or_interface_index
+                .entry(key.clone())
+                .or_insert(false);
+            *entry = *entry || is_abstract_or_interface;
+        }
+        if class.class_type.is_inherited() {
+            for key in [&class.name, &class.id] {
+                self.inherited_type_index
+                    .insert(key.clone(), class.class_type);
+            }
+        }
+
+        // A stub and its implementation describe the same class
+        if let Some(existing) = self
+            .classes
+            .iter_mut()
+            .find(|existing| existing.id == class.id && existing.is_stub != class.is_stub)
+        {
+            if class.is_stub {
+                existing.merge_stub(class);
+            } else {
+                let stub = std::mem::replace(existing, class);
+                existing.merge_stub(stub);
+            }
+            return;
+        }
+
+        // Conditional definitions of the same class are merged rather than drawn twice
+        if let Some(existing) = self.classes.iter_mut().find(|existing| {
+            existing.id == class.id && (existing.is_conditional || class.is_conditional)
+        }) {
+            existing.merge_definition(class);
+            return;
+        }
+
         self.classes.push(class);
     }
 
@@ -152,6 +710,7 @@ impl Diagram {
         self.compositions.push(composition);
     }
 
+    /// Whether a class with the given name or id has been seen as abstract or an interface.
     #[must_use]
     pub fn is_abstract_or_interface(&self, name: &str) -> bool {
         self.abstract_or_interface_index
@@ -160,8 +719,16 @@ impl Diagram {
             .unwrap_or(false)
     }
 
+    /// The class type that subclasses of the class with the given name or id inherit, if any.
+    #[must_use]
+    pub fn inherited_class_type(&self, name: &str) -> Option<ClassType> {
+        self.inherited_type_index.get(name).copied()
+    }
+
     pub fn extend(&mut self, other: Diagram) {
-        self.classes.extend(other.classes);
+        for class in other.classes {
+            self.add_class(class);
+        }
         self.relationships.extend(other.relationships);
         self.compositions.extend(other.compositions);
         for (name, other_flag) in other.abstract_or_interface_index {
@@ -170,27 +737,30 @@ impl Diagram {
                 .and_modify(|v| *v = *v || other_flag)
                 .or_insert(other_flag);
         }
+        for (name, class_type) in other.inherited_type_index {
+            self.inherited_type_index.entry(name).or_insert(class_type);
+        }
     }
 
     /// Return classes in a deterministic topological order based on relationships and compositions,
-    /// without mutating the diagram. Classes are de-duplicated by name (first occurrence wins).
+    /// without mutating the diagram. Classes are de-duplicated by id (first occurrence wins).
     #[must_use]
     pub fn classes_topologically_sorted_unique(&self) -> Vec<&ClassNode> {
         use std::collections::{HashMap, HashSet};
 
         // Topological sort with deterministic iteration.
         fn visit<'a>(
-            name: &'a str,
+            id: &'a str,
             dependencies: &HashMap<&'a str, HashSet<&'a str>>,
             visited: &mut HashSet<&'a str>,
             sorted: &mut Vec<&'a str>,
         ) {
-            if visited.contains(name) {
+            if visited.contains(id) {
                 return;
             }
-            visited.insert(name);
+            visited.insert(id);
 
-            if let Some(deps) = dependencies.get(name) {
+            if let Some(deps) = dependencies.get(id) {
                 let mut sorted_deps: Vec<_> = deps.iter().copied().collect();
                 sorted_deps.sort_unstable();
                 for dep in sorted_deps {
@@ -198,88 +768,392 @@ impl Diagram {
                 }
             }
 
-            sorted.push(name);
+            sorted.push(id);
         }
 
-        // Build a name -> first ClassNode map (stable de-dupe).
-        let mut class_map: HashMap<&str, &ClassNode> = HashMap::new();
-        for class in &self.classes {
-            class_map.entry(class.name.as_str()).or_insert(class);
-        }
+        let index = self.class_index();
 
         // Build dependency graph from relationships and compositions.
         let mut dependencies: HashMap<&str, HashSet<&str>> = HashMap::new();
 
         for relationship in &self.relationships {
-            dependencies
-                .entry(relationship.from.as_str())
-                .or_default()
-                .insert(relationship.to.as_str());
+            if let Some(target) = index.resolve(&relationship.to) {
+                dependencies
+                    .entry(relationship.from.as_str())
+                    .or_default()
+                    .insert(target.id.as_str());
+            }
         }
 
         for composition in &self.compositions {
-            dependencies
-                .entry(composition.container.as_str())
-                .or_default()
-                .insert(composition.contained.as_str());
+            if let Some(target) = index.resolve(&composition.contained) {
+                dependencies
+                    .entry(composition.container.as_str())
+                    .or_default()
+                    .insert(target.id.as_str());
+            }
         }
 
         let mut visited: HashSet<&str> = HashSet::new();
-        let mut sorted_names: Vec<&str> = Vec::new();
+        let mut sorted_ids: Vec<&str> = Vec::new();
 
-        let mut class_names: Vec<&str> = class_map.keys().copied().collect();
-        class_names.sort_unstable();
-        for name in class_names {
-            visit(name, &dependencies, &mut visited, &mut sorted_names);
+        let mut class_ids: Vec<&str> = index.by_id.keys().copied().collect();
+        class_ids.sort_unstable();
+        for id in class_ids {
+            visit(id, &dependencies, &mut visited, &mut sorted_ids);
         }
 
-        sorted_names
+        sorted_ids
             .into_iter()
-            .filter_map(|name| class_map.get(name).copied())
+            .filter_map(|id| index.get(id))
             .collect()
     }
+
+    /// Index the diagram's classes (first occurrence of each id wins) for resolving edges.
+    #[must_use]
+    pub fn class_index(&self) -> ClassIndex<'_> {
+        ClassIndex::new(&self.classes)
+    }
+}
+
+/// Lookup of a diagram's classes by id and by name, used to resolve edge endpoints.
+#[derive(Debug, Default)]
+pub struct ClassIndex<'a> {
+    by_id: std::collections::HashMap<&'a str, &'a ClassNode>,
+    by_name: std::collections::HashMap<&'a str, Vec<&'a ClassNode>>,
+}
+
+impl<'a> ClassIndex<'a> {
+    #[must_use]
+    pub fn new(classes: &'a [ClassNode]) -> Self {
+        let mut index = Self::default();
+        for class in classes {
+            if index.by_id.contains_key(class.id.as_str()) {
+                continue;
+            }
+            index.by_id.insert(&class.id, class);
+            index.by_name.entry(&class.name).or_default().push(class);
+        }
+        index
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&'a ClassNode> {
+        self.by_id.get(id).copied()
+    }
+
+    /// Resolve an edge endpoint to a class in the diagram.
+    ///
+    /// References resolved through the imports match by exact id, or by a unique id suffix
+    /// (so `models.User` finds `src.models.User` when `src` is not a package). Unresolved
+    /// references fall back to a unique class with the same name.
+    #[must_use]
+    pub fn resolve(&self, target: &ClassRef) -> Option<&'a ClassNode> {
+        let Some(id) = target.id.as_deref() else {
+            return match self.by_name.get(target.name.as_str())?.as_slice() {
+                [class] => Some(*class),
+                _ => None,
+            };
+        };
+
+        if let Some(class) = self.get(id) {
+            return Some(class);
+        }
+
+        let suffix = format!(".{id}");
+        let mut candidates = self
+            .by_id
+            .iter()
+            .filter(|(class_id, _)| class_id.ends_with(&suffix))
+            .map(|(_, class)| *class);
+        match (candidates.next(), candidates.next()) {
+            (Some(class), None) => Some(class),
+            _ => None,
+        }
+    }
+
+    /// Whether another class in the diagram has the same name, so ids must be used to tell
+    /// them apart.
+    #[must_use]
+    pub fn is_ambiguous(&self, class: &ClassNode) -> bool {
+        self.by_name
+            .get(class.name.as_str())
+            .is_some_and(|classes| classes.len() > 1)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_visibility_policy() {
+        for policy in [VisibilityPolicy::Underscore, VisibilityPolicy::Mangling] {
+            assert_eq!(policy.visibility_for_name("name"), Visibility::Public);
+            assert_eq!(policy.visibility_for_name("__init__"), Visibility::Public);
+            assert_eq!(policy.visibility_for_name("__token"), Visibility::Private);
+        }
+        assert_eq!(
+            VisibilityPolicy::Underscore.visibility_for_name("_cache"),
+            Visibility::Private
+        );
+        assert_eq!(
+            VisibilityPolicy::Mangling.visibility_for_name("_cache"),
+            Visibility::Protected
+        );
+
+        assert!(Visibility::Public.is_at_least(Visibility::Protected));
+        assert!(Visibility::Protected.is_at_least(Visibility::Protected));
+        assert!(!Visibility::Private.is_at_least(Visibility::Protected));
+    }
+
+    #[test]
+    fn test_add_class_merges_conditional_definitions() {
+        let class = |attribute: &str, is_conditional: bool| ClassNode {
+            id: "Compat".to_string(),
+            name: "Compat".to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes: vec![Attribute::new(attribute, "int", Visibility::Public)],
+            methods: vec![],
+            is_conditional,
+            is_stub: false,
+            docstring: None,
+        };
+
+        let mut diagram = Diagram::new();
+        diagram.add_class(class("new_api", true));
+        diagram.add_class(class("old_api", true));
+        diagram.add_class(class("new_api", true));
+        assert_eq!(diagram.classes.len(), 1);
+        let names: Vec<_> = diagram.classes[0]
+            .attributes
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(names, ["new_api", "old_api"]);
+
+        // Unconditional redefinitions are kept as-is (first occurrence of an id wins when rendering)
+        let mut diagram = Diagram::new();
+        diagram.add_class(class("new_api", false));
+        diagram.add_class(class("old_api", false));
+        assert_eq!(diagram.classes.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_definition_keeps_first_method_group() {
+        let method = |name: &str, return_type: &str, decorators: &[&str]| MethodSignature {
+            name: name.to_string(),
+            parameters: vec![],
+            return_type: Some(return_type.to_string()),
+            inferred_return_type: None,
+            visibility: Visibility::Public,
+            is_static: false,
+            is_abstract: false,
+            is_async: false,
+            decorators: decorators.iter().map(ToString::to_string).collect(),
+        };
+        let class = |methods| ClassNode {
+            id: "Compat".to_string(),
+            name: "Compat".to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes: vec![],
+            methods,
+            is_conditional: true,
+            is_stub: false,
+            docstring: None,
+        };
+
+        let mut merged = class(vec![
+            method("get", "int", &["@overload"]),
+            method("get", "str", &["@overload"]),
+            method("get", "int | str", &[]),
+        ]);
+        merged.merge_definition(class(vec![
+            method("get", "object", &[]),
+            method("close", "None", &[]),
+        ]));
+        let returns: Vec<_> = merged
+            .methods
+            .iter()
+            .map(|method| (method.name.as_str(), method.return_type.as_deref()))
+            .collect();
+        assert_eq!(
+            returns,
+            [
+                ("get", Some("int")),
+                ("get", Some("str")),
+                ("get", Some("int | str")),
+                ("close", Some("None")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_index_resolves_refs() {
+        let class = |id: &str| ClassNode {
+            id: id.to_string(),
+            name: id.rsplit('.').next().unwrap().to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes: vec![],
+            methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
+        };
+        let classes = vec![
+            class("src.app.models.User"),
+            class("app.a.Config"),
+            class("app.b.Config"),
+        ];
+        let index = ClassIndex::new(&classes);
+        let resolve = |id: Option<&str>, name: &str| {
+            index
+                .resolve(&ClassRef {
+                    id: id.map(str::to_string),
+                    name: name.to_string(),
+                })
+                .map(|class| class.id.as_str())
+        };
+
+        assert_eq!(
+            resolve(Some("app.models.User"), "app.models.User"),
+            Some("src.app.models.User")
+        );
+        assert_eq!(resolve(None, "User"), Some("src.app.models.User"));
+        assert_eq!(
+            resolve(Some("app.b.Config"), "Config"),
+            Some("app.b.Config")
+        );
+        assert_eq!(resolve(None, "Config"), None);
+        assert_eq!(
+            resolve(Some("pydantic.BaseModel"), "pydantic.BaseModel"),
+            None
+        );
+        assert!(index.is_ambiguous(&classes[1]));
+        assert!(!index.is_ambiguous(&classes[0]));
+    }
+
+    #[test]
+    fn test_add_class_merges_stub_into_implementation() {
+        let method = |name: &str, return_type: Option<&str>| MethodSignature {
+            name: name.to_string(),
+            parameters: vec![],
+            return_type: return_type.map(str::to_string),
+            inferred_return_type: None,
+            visibility: Visibility::Public,
+            is_static: false,
+            is_abstract: false,
+            is_async: false,
+            decorators: vec![],
+        };
+        let class = |is_stub: bool, attributes, methods| ClassNode {
+            id: "client.Client".to_string(),
+            name: "Client".to_string(),
+            type_params: None,
+            class_type: ClassType::Regular,
+            attributes,
+            methods,
+            is_conditional: false,
+            is_stub,
+            docstring: None,
+        };
+
+        let implementation = class(
+            false,
+            vec![Attribute::new("name", "Any", Visibility::Public)],
+            vec![method("get", None), method("_retry", None)],
+        );
+        let stub = class(
+            true,
+            vec![Attribute::new("name", "str", Visibility::Public)],
+            vec![method("get", Some("bytes")), method("close", Some("None"))],
+        );
+
+        // The stub wins regardless of which file is seen first
+        for (first, second) in [
+            (implementation.clone(), stub.clone()),
+            (stub, implementation),
+        ] {
+            let mut diagram = Diagram::new();
+            diagram.add_class(first);
+            diagram.add_class(second);
+            assert_eq!(diagram.classes.len(), 1);
+
+            let merged = &diagram.classes[0];
+            assert_eq!(
+                merged.attributes,
+                [Attribute::new("name", "str", Visibility::Public)]
+            );
+            assert_eq!(
+                merged.methods,
+                [
+                    method("get", Some("bytes")),
+                    method("_retry", None),
+                    method("close", Some("None")),
+                ]
+            );
+        }
+    }
+
     #[test]
     fn test_diagram_extend_concatenates_all_fields() {
         let mut a = Diagram::new();
         a.add_class(ClassNode {
+            id: "A1".to_string(),
             name: "A1".to_string(),
             type_params: None,
             class_type: ClassType::Regular,
             attributes: vec![],
             methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
         });
         a.add_relationship(RelationshipEdge {
             from: "A1".to_string(),
-            to: "Base".to_string(),
+            to: "Base".into(),
             relation_type: RelationType::Inheritance,
         });
         a.add_composition(CompositionEdge {
             container: "A1".to_string(),
-            contained: "Widget".to_string(),
+            contained: "Widget".into(),
+            kind: CompositionKind::Composition,
+            cardinality: Cardinality::One,
+            qualifier: None,
+            fields: vec!["widget".to_string()],
+            back_populates: None,
+            inverse_cardinality: None,
         });
 
         let mut b = Diagram::new();
         b.add_class(ClassNode {
+            id: "B1".to_string(),
             name: "B1".to_string(),
             type_params: None,
             class_type: ClassType::Abstract,
             attributes: vec![],
             methods: vec![],
+            is_conditional: false,
+            is_stub: false,
+            docstring: None,
         });
         b.add_relationship(RelationshipEdge {
             from: "B1".to_string(),
-            to: "Base".to_string(),
+            to: "Base".into(),
             relation_type: RelationType::Implementation,
         });
         b.add_composition(CompositionEdge {
             container: "B1".to_string(),
-            contained: "Gadget".to_string(),
+            contained: "Gadget".into(),
+            kind: CompositionKind::Aggregation,
+            cardinality: Cardinality::Many,
+            qualifier: None,
+            fields: vec!["gadgets".to_string()],
+            back_populates: None,
+            inverse_cardinality: None,
         });
 
         a.extend(b);
@@ -289,12 +1163,12 @@ mod tests {
         assert_eq!(a.classes[1].name, "B1");
 
         assert_eq!(a.relationships.len(), 2);
-        assert_eq!(a.relationships[0].to, "Base");
-        assert_eq!(a.relationships[1].to, "Base");
+        assert_eq!(a.relationships[0].to.name, "Base");
+        assert_eq!(a.relationships[1].to.name, "Base");
 
         assert_eq!(a.compositions.len(), 2);
-        assert_eq!(a.compositions[0].contained, "Widget");
-        assert_eq!(a.compositions[1].contained, "Gadget");
+        assert_eq!(a.compositions[0].contained.name, "Widget");
+        assert_eq!(a.compositions[1].contained.name, "Gadget");
 
         assert!(a.is_abstract_or_interface("B1"));
         assert!(!a.is_abstract_or_interface("A1"));
@@ -1,10 +1,37 @@
 export type DiagramDirection = "TB" | "BT" | "LR" | "RL";
 
+export type ParameterStyle = "names" | "types" | "full";
+
+export type OverloadStyle = "collapse" | "expand";
+
+export type DocstringNotes = "none" | "line" | "paragraph";
+
+export type DiagramKind = "class" | "er";
+
+export type Visibility = "public" | "protected" | "private";
+
+export type VisibilityPolicy = "underscore" | "mangling";
+
 export type PyMermaiderClass = {
   processPythonCode(code: string): string;
   setDirection(direction: DiagramDirection): void;
   getDirection(): DiagramDirection;
   setHidePrivateMembers(hide: boolean): void;
+  setMinVisibility(visibility: Visibility): void;
+  setVisibilityPolicy(policy: VisibilityPolicy): void;
+  setParameterStyle(style: ParameterStyle): void;
+  setHideSelf(hide: boolean): void;
+  setOverloadStyle(style: OverloadStyle): void;
+  setShowEnumValues(show: boolean): void;
+  setInferReturnTypes(infer: boolean): void;
+  setDocstringTypes(docstringTypes: boolean): void;
+  setDocstrings(notes: DocstringNotes): void;
+  setDocstringMaxLength(length: number): void;
+  setDocstringLineLength(length: number): void;
+  setMergeParallelEdges(merge: boolean): void;
+  setShowDependencies(show: boolean): void;
+  setDiagramKind(kind: DiagramKind): void;
+  getDiagramKind(): DiagramKind;
 };
 
 // File tree types for the explorer